@@ -1,7 +1,13 @@
+#![feature(allocator_api)]
 #![feature(coerce_unsized)]
 #![feature(dispatch_from_dyn)]
+#![feature(layout_for_ptr)]
+#![feature(slice_range)]
+#![feature(min_specialization)]
+#![feature(trusted_len)]
 #![feature(unsize)]
 pub mod iterator;
 pub mod pointer;
+pub mod queue;
+pub mod string;
 pub mod vector;
-// pub mod string;
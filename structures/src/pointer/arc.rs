@@ -0,0 +1,460 @@
+use std::alloc::{Allocator, Global, Layout};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::marker::{PhantomData, Unsize};
+use std::mem::{self, MaybeUninit};
+use std::ops::{CoerceUnsized, Deref, DispatchFromDyn};
+use std::process;
+use std::ptr::{self, NonNull};
+use std::sync::atomic::{self, AtomicUsize, Ordering::*};
+
+/// Refcounts above this abort, so that leaked clones can never overflow the count.
+const MAX_REFCOUNT: usize = isize::MAX as usize;
+
+// repr(C) so that an `ArcInner<MaybeUninit<T>>` can be cast to an `ArcInner<T>` in `new_cyclic`
+#[repr(C)]
+struct ArcInner<T: ?Sized> {
+    strong: AtomicUsize,
+    // all strong pointers together hold one weak reference, like `Rc`
+    weak: AtomicUsize,
+    value: T,
+}
+
+/// A thread-safe reference-counted pointer.
+pub struct Arc<T: ?Sized> {
+    ptr: NonNull<ArcInner<T>>,
+    phantom: PhantomData<ArcInner<T>>,
+}
+
+/// A non-owning reference to an `Arc` allocation, see [`Arc::downgrade`].
+pub struct Weak<T: ?Sized> {
+    // dangling, with an address of usize::MAX, for `Weak::new`
+    ptr: NonNull<ArcInner<T>>,
+}
+
+// SAFETY: an `Arc` hands out `&T` on any thread and may drop `T` on any thread
+unsafe impl<T: ?Sized + Sync + Send> Send for Arc<T> {}
+unsafe impl<T: ?Sized + Sync + Send> Sync for Arc<T> {}
+
+// SAFETY: a `Weak` can be upgraded to an `Arc` on any thread
+unsafe impl<T: ?Sized + Sync + Send> Send for Weak<T> {}
+unsafe impl<T: ?Sized + Sync + Send> Sync for Weak<T> {}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Arc<U>> for Arc<T> {}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> DispatchFromDyn<Arc<U>> for Arc<T> {}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Weak<U>> for Weak<T> {}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> DispatchFromDyn<Weak<U>> for Weak<T> {}
+
+impl<T> Arc<T> {
+    pub fn new(value: T) -> Arc<T> {
+        let inner = Box::new(ArcInner {
+            strong: AtomicUsize::new(1),
+            weak: AtomicUsize::new(1),
+            value,
+        });
+        Arc {
+            ptr: NonNull::from(Box::leak(inner)),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates an `Arc` whose value can hold a `Weak` to itself. Upgrading the `Weak` inside
+    /// `data_fn` returns `None`, since the value doesn't exist yet.
+    pub fn new_cyclic<F>(data_fn: F) -> Arc<T>
+    where
+        F: FnOnce(&Weak<T>) -> T,
+    {
+        let uninit = Box::new(ArcInner {
+            strong: AtomicUsize::new(0),
+            weak: AtomicUsize::new(1),
+            value: MaybeUninit::<T>::uninit(),
+        });
+        let ptr: NonNull<ArcInner<T>> = NonNull::from(Box::leak(uninit)).cast();
+
+        // if `data_fn` panics, dropping this weak frees the allocation
+        let weak = Weak { ptr };
+        let data = data_fn(&weak);
+
+        // SAFETY: the allocation is alive, and with a strong count of 0 no upgrade can
+        // succeed before the value is written
+        unsafe {
+            let inner = ptr.as_ptr();
+            ptr::write(&raw mut (*inner).value, data);
+            // Release so that an upgrade on another thread sees the written value
+            (*inner).strong.store(1, Release);
+        }
+        mem::forget(weak);
+        Arc {
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the value if `this` is the only strong pointer, or `this` back otherwise.
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        if this
+            .inner()
+            .strong
+            .compare_exchange(1, 0, Relaxed, Relaxed)
+            .is_err()
+        {
+            return Err(this);
+        }
+        // pairs with the Release decrements of the other strong pointers
+        atomic::fence(Acquire);
+
+        let this = mem::ManuallyDrop::new(this);
+        // SAFETY: the strong count is now 0, so nobody else can reach the value
+        let value = unsafe { ptr::read(&this.inner().value) };
+        drop(Weak { ptr: this.ptr });
+        Ok(value)
+    }
+
+    /// Returns the value if `this` is the last strong pointer, and drops `this` otherwise.
+    ///
+    /// Unlike `Arc::try_unwrap(this).ok()`, when several threads call this on the clones of
+    /// one `Arc`, exactly one of them gets the value.
+    pub fn into_inner(this: Self) -> Option<T> {
+        let this = mem::ManuallyDrop::new(this);
+        if this.inner().strong.fetch_sub(1, Release) != 1 {
+            return None;
+        }
+        atomic::fence(Acquire);
+
+        // SAFETY: this was the last strong pointer
+        let value = unsafe { ptr::read(&this.inner().value) };
+        drop(Weak { ptr: this.ptr });
+        Some(value)
+    }
+}
+
+impl<T: Clone> Arc<T> {
+    /// Returns a mutable reference to the value, cloning it first if other `Arc`s point to
+    /// it. `Weak`s are disassociated instead, since they don't own the value.
+    pub fn make_mut(this: &mut Self) -> &mut T {
+        // claim the value by setting the strong count to 0, which also stops upgrades
+        if this
+            .inner()
+            .strong
+            .compare_exchange(1, 0, Acquire, Relaxed)
+            .is_err()
+        {
+            *this = Arc::new((**this).clone());
+        } else if this.inner().weak.load(Relaxed) != 1 {
+            // weaks exist, so move the value to a fresh allocation and leave them behind
+            // SAFETY: the strong count is 0, so the old value is never read or dropped again
+            unsafe {
+                let fresh = Arc::new(ptr::read(&this.inner().value));
+                let old = Weak { ptr: this.ptr };
+                ptr::write(this, fresh);
+                drop(old);
+            }
+        } else {
+            // no weaks could upgrade meanwhile, so give the claim back
+            this.inner().strong.store(1, Release);
+        }
+        // SAFETY: `this` is now the only pointer, strong or weak, to its allocation
+        unsafe { &mut (*this.ptr.as_ptr()).value }
+    }
+
+    /// Returns the value if `this` is the only strong pointer, or a clone of it otherwise.
+    pub fn unwrap_or_clone(this: Self) -> T {
+        Arc::try_unwrap(this).unwrap_or_else(|arc| (*arc).clone())
+    }
+}
+
+impl<T: ?Sized> Arc<T> {
+    #[inline]
+    fn inner(&self) -> &ArcInner<T> {
+        // SAFETY: the allocation lives as long as any strong pointer does
+        unsafe { self.ptr.as_ref() }
+    }
+
+    pub fn downgrade(this: &Self) -> Weak<T> {
+        let weak = &this.inner().weak;
+        let mut cur = weak.load(Relaxed);
+        loop {
+            // usize::MAX means `is_unique` has locked the count, so wait for it
+            if cur == usize::MAX {
+                std::hint::spin_loop();
+                cur = weak.load(Relaxed);
+                continue;
+            }
+            if cur > MAX_REFCOUNT {
+                process::abort();
+            }
+            // Acquire pairs with the Release that unlocks the count in `is_unique`
+            match weak.compare_exchange_weak(cur, cur + 1, Acquire, Relaxed) {
+                Ok(_) => return Weak { ptr: this.ptr },
+                Err(old) => cur = old,
+            }
+        }
+    }
+
+    /// Number of strong pointers, which other threads may change at any time.
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().strong.load(Relaxed)
+    }
+
+    /// Number of `Weak`s, which other threads may change at any time.
+    pub fn weak_count(this: &Self) -> usize {
+        match this.inner().weak.load(Relaxed) {
+            // locked by `is_unique`, which only happens when there are no weaks
+            usize::MAX => 0,
+            weak => weak - 1,
+        }
+    }
+
+    /// Returns a mutable reference to the value if there are no other `Arc`s or `Weak`s
+    /// pointing to it.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if this.is_unique() {
+            // SAFETY: nobody else can reach the value
+            Some(unsafe { &mut (*this.ptr.as_ptr()).value })
+        } else {
+            None
+        }
+    }
+
+    fn is_unique(&self) -> bool {
+        // lock out downgrades by taking the weak count to usize::MAX while checking the
+        // strong count, so that a weak can't be created and upgraded in between
+        if self
+            .inner()
+            .weak
+            .compare_exchange(1, usize::MAX, Acquire, Relaxed)
+            .is_err()
+        {
+            return false;
+        }
+        let unique = self.inner().strong.load(Acquire) == 1;
+        self.inner().weak.store(1, Release);
+        unique
+    }
+
+    /// Returns `true` if both point to the same allocation, ignoring vtable metadata.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ptr::addr_eq(this.ptr.as_ptr(), other.ptr.as_ptr())
+    }
+
+    pub fn as_ptr(this: &Self) -> *const T {
+        &raw const this.inner().value
+    }
+}
+
+impl<T: ?Sized> Clone for Arc<T> {
+    fn clone(&self) -> Self {
+        // a new clone can only be made from an existing one, which already synchronizes the
+        // allocation, so Relaxed is enough
+        let old = self.inner().strong.fetch_add(1, Relaxed);
+        if old > MAX_REFCOUNT {
+            process::abort();
+        }
+        Arc {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Drop for Arc<T> {
+    fn drop(&mut self) {
+        if self.inner().strong.fetch_sub(1, Release) != 1 {
+            return;
+        }
+        // make every other pointer's last use of the value happen before it is dropped
+        atomic::fence(Acquire);
+        // SAFETY: this was the last strong pointer
+        unsafe { ptr::drop_in_place(&raw mut (*self.ptr.as_ptr()).value) };
+        drop(Weak { ptr: self.ptr });
+    }
+}
+
+impl<T: ?Sized> Deref for Arc<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.inner().value
+    }
+}
+
+impl<T> Weak<T> {
+    /// Creates a `Weak` that never upgrades, without allocating.
+    pub const fn new() -> Weak<T> {
+        Weak {
+            ptr: NonNull::without_provenance(std::num::NonZeroUsize::MAX),
+        }
+    }
+}
+
+impl<T: ?Sized> Weak<T> {
+    #[inline]
+    fn inner(&self) -> Option<&ArcInner<T>> {
+        if self.ptr.as_ptr().cast::<()>().addr() == usize::MAX {
+            None
+        } else {
+            // SAFETY: a weak reference keeps the allocation, though not the value, alive
+            Some(unsafe { self.ptr.as_ref() })
+        }
+    }
+
+    /// Returns an `Arc` to the value, or `None` if it has already been dropped.
+    pub fn upgrade(&self) -> Option<Arc<T>> {
+        let inner = self.inner()?;
+        // never go from 0 to 1, since the value may already be dropped
+        inner
+            .strong
+            .fetch_update(Acquire, Relaxed, |n| {
+                if n == 0 {
+                    return None;
+                }
+                if n > MAX_REFCOUNT {
+                    process::abort();
+                }
+                Some(n + 1)
+            })
+            .ok()?;
+        Some(Arc {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn strong_count(&self) -> usize {
+        self.inner().map_or(0, |inner| inner.strong.load(Relaxed))
+    }
+
+    /// Number of `Weak`s pointing to the allocation, or 0 once the value has been dropped.
+    pub fn weak_count(&self) -> usize {
+        let Some(inner) = self.inner() else {
+            return 0;
+        };
+        let weak = inner.weak.load(Acquire);
+        if inner.strong.load(Relaxed) == 0 {
+            0
+        } else {
+            // the strong pointers' shared weak may be dropped between the two loads
+            weak.saturating_sub(1)
+        }
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        ptr::addr_eq(self.ptr.as_ptr(), other.ptr.as_ptr())
+    }
+}
+
+impl<T: ?Sized> Clone for Weak<T> {
+    fn clone(&self) -> Self {
+        if let Some(inner) = self.inner() {
+            let old = inner.weak.fetch_add(1, Relaxed);
+            if old > MAX_REFCOUNT {
+                process::abort();
+            }
+        }
+        Weak { ptr: self.ptr }
+    }
+}
+
+impl<T: ?Sized> Drop for Weak<T> {
+    fn drop(&mut self) {
+        let Some(inner) = self.inner() else {
+            return;
+        };
+        if inner.weak.fetch_sub(1, Release) != 1 {
+            return;
+        }
+        atomic::fence(Acquire);
+        // SAFETY: the value is gone and this was the last reference to the allocation,
+        // which was made by `Box` with the global allocator
+        unsafe {
+            let layout = Layout::for_value_raw(self.ptr.as_ptr());
+            Global.deallocate(self.ptr.cast(), layout);
+        }
+    }
+}
+
+impl<T> Default for Weak<T> {
+    fn default() -> Self {
+        Weak::new()
+    }
+}
+
+impl<T: ?Sized> Debug for Weak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(Weak)")
+    }
+}
+
+impl<T: Default> Default for Arc<T> {
+    fn default() -> Self {
+        Arc::new(T::default())
+    }
+}
+
+impl<T> From<T> for Arc<T> {
+    fn from(value: T) -> Self {
+        Arc::new(value)
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for Arc<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized> Borrow<T> for Arc<T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for Arc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + Display> Display for Arc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> fmt::Pointer for Arc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&Arc::as_ptr(self), f)
+    }
+}
+
+impl<T: ?Sized + PartialEq> PartialEq for Arc<T> {
+    fn eq(&self, other: &Arc<T>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: ?Sized + Eq> Eq for Arc<T> {}
+
+impl<T: ?Sized + PartialOrd> PartialOrd for Arc<T> {
+    fn partial_cmp(&self, other: &Arc<T>) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: ?Sized + Ord> Ord for Arc<T> {
+    fn cmp(&self, other: &Arc<T>) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: ?Sized + Hash> Hash for Arc<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
@@ -0,0 +1,310 @@
+use std::alloc::{handle_alloc_error, Allocator, Global, Layout};
+use std::borrow::{Borrow, BorrowMut};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::marker::{PhantomData, Unsize};
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ops::{CoerceUnsized, Deref, DerefMut, DispatchFromDyn};
+use std::ptr::{self, NonNull};
+
+use crate::vector::{GrowthPolicy, Vec};
+
+/// An owning pointer to a heap allocation, made with the global allocator like `RawVec` does.
+pub struct Box<T: ?Sized> {
+    ptr: NonNull<T>,
+    phantom: PhantomData<T>,
+}
+
+// SAFETY: a `Box` owns its value like a plain `T` would
+unsafe impl<T: ?Sized + Send> Send for Box<T> {}
+unsafe impl<T: ?Sized + Sync> Sync for Box<T> {}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Box<U>> for Box<T> {}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> DispatchFromDyn<Box<U>> for Box<T> {}
+
+/// Allocates room for one `T`, or returns a dangling pointer if `T` is zero-sized.
+fn allocate<T>() -> NonNull<T> {
+    let layout = Layout::new::<T>();
+    if layout.size() == 0 {
+        return NonNull::dangling();
+    }
+    // the allocator reports failure instead of returning a null pointer
+    match Global.allocate(layout) {
+        Ok(ptr) => ptr.cast(),
+        Err(_) => handle_alloc_error(layout),
+    }
+}
+
+/// # Safety
+///
+/// `ptr` must come from a `Box`, and its value must already be dropped or moved out.
+unsafe fn deallocate<T: ?Sized>(ptr: NonNull<T>) {
+    unsafe {
+        let layout = Layout::for_value_raw(ptr.as_ptr());
+        if layout.size() != 0 {
+            Global.deallocate(ptr.cast(), layout);
+        }
+    }
+}
+
+impl<T> Box<T> {
+    pub fn new(value: T) -> Box<T> {
+        let ptr = allocate::<T>();
+        // SAFETY: ptr is valid for writes of a `T`
+        unsafe { ptr.write(value) };
+        Box {
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Allocates without initializing, so that the value can be written in place.
+    pub fn new_uninit() -> Box<MaybeUninit<T>> {
+        Box {
+            ptr: allocate::<MaybeUninit<T>>(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Moves the value out and frees the allocation.
+    pub fn into_inner(this: Self) -> T {
+        let this = ManuallyDrop::new(this);
+        // SAFETY: the value is read once, and the allocation freed without dropping it again
+        unsafe {
+            let value = this.ptr.read();
+            deallocate(this.ptr);
+            value
+        }
+    }
+}
+
+impl<T> Box<MaybeUninit<T>> {
+    /// # Safety
+    ///
+    /// The value must have been initialized.
+    pub unsafe fn assume_init(self) -> Box<T> {
+        let ptr = Box::into_raw(self);
+        // SAFETY: MaybeUninit<T> has the same layout as T
+        unsafe { Box::from_raw(ptr.cast::<T>()) }
+    }
+
+    /// Initializes the value and returns the box as initialized.
+    pub fn write(mut boxed: Self, value: T) -> Box<T> {
+        (*boxed).write(value);
+        // SAFETY: the value was just written
+        unsafe { boxed.assume_init() }
+    }
+}
+
+impl<T: ?Sized> Box<T> {
+    /// Takes ownership of an allocation made by `Box::into_raw`, `Box::leak` or std's `Box`.
+    ///
+    /// # Safety
+    ///
+    /// `raw` must point to a live value allocated with the global allocator and the layout of
+    /// that value, and nothing else may own it.
+    pub unsafe fn from_raw(raw: *mut T) -> Box<T> {
+        Box {
+            // SAFETY: the caller guarantees raw came from a box, which is never null
+            ptr: unsafe { NonNull::new_unchecked(raw) },
+            phantom: PhantomData,
+        }
+    }
+
+    /// Consumes the box without freeing it. Rebuild it with `from_raw` to avoid a leak.
+    pub fn into_raw(this: Self) -> *mut T {
+        ManuallyDrop::new(this).ptr.as_ptr()
+    }
+
+    /// Consumes the box without freeing it, so the value lives on forever.
+    pub fn leak<'a>(this: Self) -> &'a mut T
+    where
+        T: 'a,
+    {
+        // SAFETY: the allocation is never freed, and nothing else owns the value
+        unsafe { &mut *Box::into_raw(this) }
+    }
+
+    /// Hands the allocation over to std's `Box`. The reverse is a `From` impl, which the
+    /// orphan rules don't allow in this direction.
+    pub fn into_std(this: Self) -> std::boxed::Box<T> {
+        // SAFETY: std's Box allocates the same layout with the same global allocator
+        unsafe { std::boxed::Box::from_raw(Box::into_raw(this)) }
+    }
+
+    pub fn as_ptr(this: &Self) -> *const T {
+        this.ptr.as_ptr()
+    }
+
+    pub fn as_mut_ptr(this: &mut Self) -> *mut T {
+        this.ptr.as_ptr()
+    }
+}
+
+impl<T: ?Sized> Drop for Box<T> {
+    fn drop(&mut self) {
+        // SAFETY: the box owns the value and the allocation, and both are freed only here
+        unsafe {
+            ptr::drop_in_place(self.ptr.as_ptr());
+            deallocate(self.ptr);
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for Box<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        // SAFETY: the value lives as long as the box
+        unsafe { self.ptr.as_ref() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for Box<T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: the box owns the value uniquely
+        unsafe { self.ptr.as_mut() }
+    }
+}
+
+impl<T: Clone> Clone for Box<T> {
+    fn clone(&self) -> Self {
+        Box::new((**self).clone())
+    }
+}
+
+impl<T: Clone> Clone for Box<[T]> {
+    fn clone(&self) -> Self {
+        (**self).iter().cloned().collect::<Vec<T>>().into()
+    }
+}
+
+impl<T: Default> Default for Box<T> {
+    fn default() -> Self {
+        Box::new(T::default())
+    }
+}
+
+impl<T> From<T> for Box<T> {
+    fn from(value: T) -> Self {
+        Box::new(value)
+    }
+}
+
+impl<T: Clone> From<&[T]> for Box<[T]> {
+    fn from(slice: &[T]) -> Self {
+        slice.iter().cloned().collect::<Vec<T>>().into()
+    }
+}
+
+impl<T, G: GrowthPolicy> From<Vec<T, Global, G>> for Box<[T]> {
+    /// Shrinks the allocation to fit the elements and takes it over.
+    fn from(mut vec: Vec<T, Global, G>) -> Self {
+        vec.shrink_to_fit();
+        let (ptr, len, _, _) = vec.into_raw_parts_with_alloc();
+        // SAFETY: after shrinking, the vector's Layout::array::<T>(len) allocation is exactly
+        // the layout of a [T] of len elements
+        unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(ptr, len)) }
+    }
+}
+
+impl<T> From<Box<[T]>> for Vec<T> {
+    /// Takes over the allocation without copying.
+    fn from(boxed: Box<[T]>) -> Self {
+        let len = boxed.len();
+        let ptr = Box::into_raw(boxed).cast::<T>();
+        // SAFETY: a boxed slice of len elements is allocated with Layout::array::<T>(len)
+        unsafe { Vec::from_raw_parts(ptr, len, len) }
+    }
+}
+
+impl<T: ?Sized> From<std::boxed::Box<T>> for Box<T> {
+    fn from(boxed: std::boxed::Box<T>) -> Self {
+        // SAFETY: std's Box allocates the same layout with the same global allocator
+        unsafe { Box::from_raw(std::boxed::Box::into_raw(boxed)) }
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for Box<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized> AsMut<T> for Box<T> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: ?Sized> Borrow<T> for Box<T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized> BorrowMut<T> for Box<T> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for Box<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + Display> Display for Box<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> fmt::Pointer for Box<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&Box::as_ptr(self), f)
+    }
+}
+
+impl<T: ?Sized + PartialEq> PartialEq for Box<T> {
+    fn eq(&self, other: &Box<T>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: ?Sized + Eq> Eq for Box<T> {}
+
+impl<T: ?Sized + PartialOrd> PartialOrd for Box<T> {
+    fn partial_cmp(&self, other: &Box<T>) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: ?Sized + Ord> Ord for Box<T> {
+    fn cmp(&self, other: &Box<T>) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: ?Sized + Hash> Hash for Box<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
+
+impl<I: Iterator + ?Sized> Iterator for Box<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        (**self).next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (**self).size_hint()
+    }
+}
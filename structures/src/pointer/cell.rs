@@ -155,6 +155,20 @@ impl<T: Display + Copy> Display for Cell<T> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + Copy> serde::Serialize for Cell<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.get().serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de> + Copy> serde::Deserialize<'de> for Cell<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Cell::new)
+    }
+}
+
 impl<T: CoerceUnsized<U>, U> CoerceUnsized<Cell<U>> for Cell<T> {}
 
 impl<T: DispatchFromDyn<U>, U> DispatchFromDyn<Cell<U>> for Cell<T> {}
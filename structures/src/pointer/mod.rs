@@ -1,6 +1,12 @@
+pub mod arc;
+mod boxed;
 mod cell;
 mod refcell;
+pub mod rc;
 
 pub use std::cell::UnsafeCell;
+pub use arc::Arc;
+pub use boxed::Box;
 pub use cell::Cell;
+pub use rc::Rc;
 pub use refcell::{RefCell, Ref, RefMut};
\ No newline at end of file
@@ -0,0 +1,401 @@
+use super::Cell;
+
+use std::alloc::{Allocator, Global, Layout};
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::marker::{PhantomData, Unsize};
+use std::mem::{self, MaybeUninit};
+use std::ops::{CoerceUnsized, Deref, DispatchFromDyn};
+use std::process;
+use std::ptr::{self, NonNull};
+
+// repr(C) so that an `RcBox<MaybeUninit<T>>` can be cast to an `RcBox<T>` in `new_cyclic`
+#[repr(C)]
+struct RcBox<T: ?Sized> {
+    strong: Cell<usize>,
+    // all strong pointers together hold one weak reference, which keeps the allocation alive
+    // until the last `Weak` is gone too
+    weak: Cell<usize>,
+    value: T,
+}
+
+impl<T: ?Sized> RcBox<T> {
+    #[inline]
+    fn inc_strong(&self) {
+        let strong = self.strong.get().wrapping_add(1);
+        self.strong.set(strong);
+        // leaking clones in a loop must not overflow the count into a use-after-free
+        if strong == 0 {
+            process::abort();
+        }
+    }
+
+    #[inline]
+    fn dec_strong(&self) {
+        self.strong.set(self.strong.get() - 1);
+    }
+
+    #[inline]
+    fn inc_weak(&self) {
+        let weak = self.weak.get().wrapping_add(1);
+        self.weak.set(weak);
+        if weak == 0 {
+            process::abort();
+        }
+    }
+
+    #[inline]
+    fn dec_weak(&self) {
+        self.weak.set(self.weak.get() - 1);
+    }
+}
+
+/// A single-threaded reference-counted pointer, counting with our own `Cell`.
+pub struct Rc<T: ?Sized> {
+    ptr: NonNull<RcBox<T>>,
+    phantom: PhantomData<RcBox<T>>,
+}
+
+/// A non-owning reference to an `Rc` allocation, see [`Rc::downgrade`].
+pub struct Weak<T: ?Sized> {
+    // dangling, with an address of usize::MAX, for `Weak::new`
+    ptr: NonNull<RcBox<T>>,
+}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Rc<U>> for Rc<T> {}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> DispatchFromDyn<Rc<U>> for Rc<T> {}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> CoerceUnsized<Weak<U>> for Weak<T> {}
+
+impl<T: ?Sized + Unsize<U>, U: ?Sized> DispatchFromDyn<Weak<U>> for Weak<T> {}
+
+impl<T> Rc<T> {
+    pub fn new(value: T) -> Rc<T> {
+        let inner = Box::new(RcBox {
+            strong: Cell::new(1),
+            weak: Cell::new(1),
+            value,
+        });
+        Rc {
+            ptr: NonNull::from(Box::leak(inner)),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Creates an `Rc` whose value can hold a `Weak` to itself. Upgrading the `Weak` inside
+    /// `data_fn` returns `None`, since the value doesn't exist yet.
+    pub fn new_cyclic<F>(data_fn: F) -> Rc<T>
+    where
+        F: FnOnce(&Weak<T>) -> T,
+    {
+        let uninit = Box::new(RcBox {
+            strong: Cell::new(0),
+            weak: Cell::new(1),
+            value: MaybeUninit::<T>::uninit(),
+        });
+        let ptr: NonNull<RcBox<T>> = NonNull::from(Box::leak(uninit)).cast();
+
+        // this weak is the one all strong pointers share; if `data_fn` panics, dropping it
+        // frees the allocation without touching the missing value
+        let weak = Weak { ptr };
+        let data = data_fn(&weak);
+
+        // SAFETY: the allocation is alive, and with a strong count of 0 nobody can read the
+        // value before it is written
+        unsafe {
+            let inner = ptr.as_ptr();
+            ptr::write(&raw mut (*inner).value, data);
+            (*inner).strong.set(1);
+        }
+        mem::forget(weak);
+        Rc {
+            ptr,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the value if `this` is the only strong pointer, or `this` back otherwise.
+    /// Outstanding `Weak`s can no longer upgrade afterwards.
+    pub fn try_unwrap(this: Self) -> Result<T, Self> {
+        if Rc::strong_count(&this) != 1 {
+            return Err(this);
+        }
+
+        let this = mem::ManuallyDrop::new(this);
+        // SAFETY: this is the last strong pointer, so the value is moved out exactly once
+        let value = unsafe { ptr::read(&this.inner().value) };
+        this.inner().dec_strong();
+        // release the weak reference the strong pointers held
+        drop(Weak { ptr: this.ptr });
+        Ok(value)
+    }
+
+    /// Like `try_unwrap`, but drops `this` when other strong pointers remain.
+    pub fn into_inner(this: Self) -> Option<T> {
+        Rc::try_unwrap(this).ok()
+    }
+}
+
+impl<T: Clone> Rc<T> {
+    /// Returns a mutable reference to the value, cloning it first if other `Rc`s point to
+    /// it. `Weak`s are disassociated instead, since they don't own the value.
+    pub fn make_mut(this: &mut Self) -> &mut T {
+        if Rc::strong_count(this) != 1 {
+            *this = Rc::new((**this).clone());
+        } else if Rc::weak_count(this) != 0 {
+            // move the value to a fresh allocation and leave the weaks behind a dead one
+            // SAFETY: this is the only strong pointer, and the old value is never dropped
+            // since its strong count is set to 0
+            unsafe {
+                let fresh = Rc::new(ptr::read(&this.inner().value));
+                this.inner().dec_strong();
+                this.inner().dec_weak();
+                ptr::write(this, fresh);
+            }
+        }
+        // SAFETY: `this` is now the only pointer, strong or weak, to its allocation
+        unsafe { &mut (*this.ptr.as_ptr()).value }
+    }
+
+    /// Returns the value if `this` is the only strong pointer, or a clone of it otherwise.
+    pub fn unwrap_or_clone(this: Self) -> T {
+        Rc::try_unwrap(this).unwrap_or_else(|rc| (*rc).clone())
+    }
+}
+
+impl<T: ?Sized> Rc<T> {
+    #[inline]
+    fn inner(&self) -> &RcBox<T> {
+        // SAFETY: the allocation lives as long as any strong pointer does
+        unsafe { self.ptr.as_ref() }
+    }
+
+    pub fn downgrade(this: &Self) -> Weak<T> {
+        this.inner().inc_weak();
+        Weak { ptr: this.ptr }
+    }
+
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().strong.get()
+    }
+
+    /// Number of `Weak`s pointing to this allocation.
+    pub fn weak_count(this: &Self) -> usize {
+        this.inner().weak.get() - 1
+    }
+
+    /// Returns a mutable reference to the value if there are no other `Rc`s or `Weak`s
+    /// pointing to it.
+    pub fn get_mut(this: &mut Self) -> Option<&mut T> {
+        if Rc::strong_count(this) == 1 && Rc::weak_count(this) == 0 {
+            // SAFETY: nobody else can reach the value
+            Some(unsafe { &mut (*this.ptr.as_ptr()).value })
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if both point to the same allocation, ignoring vtable metadata.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        ptr::addr_eq(this.ptr.as_ptr(), other.ptr.as_ptr())
+    }
+
+    pub fn as_ptr(this: &Self) -> *const T {
+        &raw const this.inner().value
+    }
+}
+
+impl<T: ?Sized> Clone for Rc<T> {
+    fn clone(&self) -> Self {
+        self.inner().inc_strong();
+        Rc {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized> Drop for Rc<T> {
+    fn drop(&mut self) {
+        self.inner().dec_strong();
+        if self.inner().strong.get() == 0 {
+            // SAFETY: this was the last strong pointer
+            unsafe { ptr::drop_in_place(&raw mut (*self.ptr.as_ptr()).value) };
+            // release the weak reference the strong pointers held
+            drop(Weak { ptr: self.ptr });
+        }
+    }
+}
+
+impl<T: ?Sized> Deref for Rc<T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        &self.inner().value
+    }
+}
+
+impl<T> Weak<T> {
+    /// Creates a `Weak` that never upgrades, without allocating.
+    pub const fn new() -> Weak<T> {
+        Weak {
+            ptr: NonNull::without_provenance(std::num::NonZeroUsize::MAX),
+        }
+    }
+}
+
+impl<T: ?Sized> Weak<T> {
+    #[inline]
+    fn is_dangling(&self) -> bool {
+        self.ptr.as_ptr().cast::<()>().addr() == usize::MAX
+    }
+
+    #[inline]
+    fn inner(&self) -> Option<&RcBox<T>> {
+        if self.is_dangling() {
+            None
+        } else {
+            // SAFETY: a weak reference keeps the allocation, though not the value, alive
+            Some(unsafe { self.ptr.as_ref() })
+        }
+    }
+
+    /// Returns an `Rc` to the value, or `None` if it has already been dropped.
+    pub fn upgrade(&self) -> Option<Rc<T>> {
+        let inner = self.inner()?;
+        if inner.strong.get() == 0 {
+            return None;
+        }
+        inner.inc_strong();
+        Some(Rc {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        })
+    }
+
+    pub fn strong_count(&self) -> usize {
+        self.inner().map_or(0, |inner| inner.strong.get())
+    }
+
+    /// Number of `Weak`s pointing to the allocation, or 0 once the value has been dropped.
+    pub fn weak_count(&self) -> usize {
+        match self.inner() {
+            Some(inner) if inner.strong.get() > 0 => inner.weak.get() - 1,
+            _ => 0,
+        }
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        ptr::addr_eq(self.ptr.as_ptr(), other.ptr.as_ptr())
+    }
+}
+
+impl<T: ?Sized> Clone for Weak<T> {
+    fn clone(&self) -> Self {
+        if let Some(inner) = self.inner() {
+            inner.inc_weak();
+        }
+        Weak { ptr: self.ptr }
+    }
+}
+
+impl<T: ?Sized> Drop for Weak<T> {
+    fn drop(&mut self) {
+        let Some(inner) = self.inner() else {
+            return;
+        };
+        inner.dec_weak();
+        if inner.weak.get() == 0 {
+            // SAFETY: the value is gone and this was the last reference to the allocation,
+            // which was made by `Box` with the global allocator
+            unsafe {
+                let layout = Layout::for_value_raw(self.ptr.as_ptr());
+                Global.deallocate(self.ptr.cast(), layout);
+            }
+        }
+    }
+}
+
+impl<T> Default for Weak<T> {
+    fn default() -> Self {
+        Weak::new()
+    }
+}
+
+impl<T: ?Sized> Debug for Weak<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("(Weak)")
+    }
+}
+
+impl<T: Default> Default for Rc<T> {
+    fn default() -> Self {
+        Rc::new(T::default())
+    }
+}
+
+impl<T> From<T> for Rc<T> {
+    fn from(value: T) -> Self {
+        Rc::new(value)
+    }
+}
+
+impl<T: ?Sized> AsRef<T> for Rc<T> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized> Borrow<T> for Rc<T> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+
+impl<T: ?Sized + Debug> Debug for Rc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized + Display> Display for Rc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+impl<T: ?Sized> fmt::Pointer for Rc<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Pointer::fmt(&Rc::as_ptr(self), f)
+    }
+}
+
+impl<T: ?Sized + PartialEq> PartialEq for Rc<T> {
+    fn eq(&self, other: &Rc<T>) -> bool {
+        **self == **other
+    }
+}
+
+impl<T: ?Sized + Eq> Eq for Rc<T> {}
+
+impl<T: ?Sized + PartialOrd> PartialOrd for Rc<T> {
+    fn partial_cmp(&self, other: &Rc<T>) -> Option<Ordering> {
+        (**self).partial_cmp(&**other)
+    }
+}
+
+impl<T: ?Sized + Ord> Ord for Rc<T> {
+    fn cmp(&self, other: &Rc<T>) -> Ordering {
+        (**self).cmp(&**other)
+    }
+}
+
+impl<T: ?Sized + Hash> Hash for Rc<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state)
+    }
+}
@@ -213,6 +213,24 @@ impl<T: PartialOrd + ?Sized> PartialOrd for RefCell<T> {
     }
 }
 
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for RefCell<T> {
+    /// Fails instead of panicking if the value is mutably borrowed.
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.try_borrow() {
+            Ok(value) => value.serialize(serializer),
+            Err(_) => Err(serde::ser::Error::custom("already mutably borrowed")),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for RefCell<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(RefCell::new)
+    }
+}
+
 impl<T: CoerceUnsized<U>, U> CoerceUnsized<RefCell<U>> for RefCell<T> {}
 
 pub struct BorrowRef<'b> {
@@ -255,6 +273,7 @@ pub struct Ref<'b, T: ?Sized + 'b> {
 }
 
 impl<'b, T: ?Sized> Ref<'b, T> {
+    #[allow(clippy::should_implement_trait)]
     pub fn clone(orig: &Ref<'b, T>) -> Ref<'b, T> {
         Ref {
             value: orig.value,
@@ -0,0 +1,710 @@
+use std::cmp::Reverse;
+use std::fmt::{self, Debug};
+use std::iter::Map;
+use std::mem::{self, ManuallyDrop};
+use std::num::NonZeroUsize;
+use std::ops::{Deref, DerefMut};
+use std::ptr;
+
+use super::{Queue, QueueError};
+use crate::vector::{Drain, IntoIter, Iter, Vec};
+
+/// A priority queue implemented as a max-heap on top of our `Vec`. `pop` always returns the
+/// greatest element, see [`MinHeap`] for the opposite order.
+pub struct BinaryHeap<T> {
+    data: Vec<T>,
+}
+
+impl<T: Ord> BinaryHeap<T> {
+    pub const fn new() -> Self {
+        Self { data: Vec::new() }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        let old_len = self.len();
+        self.data.push(item);
+        // SAFETY: old_len is the index of the element just pushed
+        unsafe { self.sift_up(0, old_len) };
+    }
+
+    /// Removes the greatest element, or returns `None` if the heap is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.data.pop().map(|mut item| {
+            if !self.is_empty() {
+                mem::swap(&mut item, &mut self.data[0]);
+                // SAFETY: the heap is non-empty, so 0 is in bounds
+                unsafe { self.sift_down_to_bottom(0) };
+            }
+            item
+        })
+    }
+
+    /// Returns a guard to the greatest element, which restores the heap order when dropped if
+    /// the element was changed through it.
+    pub fn peek_mut(&mut self) -> Option<PeekMut<'_, T>> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(PeekMut {
+                heap: self,
+                original_len: None,
+            })
+        }
+    }
+
+    /// Returns the elements in ascending order.
+    pub fn into_sorted_vec(mut self) -> Vec<T> {
+        let mut end = self.len();
+        while end > 1 {
+            end -= 1;
+            self.data.swap(0, end);
+            // SAFETY: end is in 1..len, so 0 is in bounds and end is a valid limit
+            unsafe { self.sift_down_range(0, end) };
+        }
+        self.into_vec()
+    }
+
+    /// Moves all elements of `other` into `self`, leaving `other` empty.
+    pub fn append(&mut self, other: &mut Self) {
+        if self.len() < other.len() {
+            mem::swap(self, other);
+        }
+
+        let start = self.data.len();
+        self.data.extend(other.data.drain(..));
+        self.rebuild_tail(start);
+    }
+
+    /// Moves the element at `pos` up until its parent is at least as large, stopping at
+    /// `start`. Returns its new position.
+    ///
+    /// # Safety
+    ///
+    /// `pos` must be less than `self.len()`.
+    unsafe fn sift_up(&mut self, start: usize, pos: usize) -> usize {
+        // SAFETY: the caller guarantees that pos < len
+        let mut hole = unsafe { Hole::new(&mut self.data, pos) };
+
+        while hole.pos() > start {
+            let parent = (hole.pos() - 1) / 2;
+            // SAFETY: parent < hole.pos(), so it is in bounds and not the hole itself
+            if hole.element() <= unsafe { hole.get(parent) } {
+                break;
+            }
+            // SAFETY: same as above
+            unsafe { hole.move_to(parent) };
+        }
+
+        hole.pos()
+    }
+
+    /// Moves the element at `pos` down until both children are at most as large, only
+    /// considering the elements before `end`.
+    ///
+    /// # Safety
+    ///
+    /// `pos < end <= self.len()`.
+    unsafe fn sift_down_range(&mut self, pos: usize, end: usize) {
+        // SAFETY: the caller guarantees that pos < end <= len
+        let mut hole = unsafe { Hole::new(&mut self.data, pos) };
+        let mut child = 2 * hole.pos() + 1;
+
+        // the loop condition keeps both children in bounds
+        while child <= end.saturating_sub(2) {
+            // SAFETY: child and child + 1 are below end and differ from the hole
+            unsafe {
+                // pick the greater of the two children
+                child += (hole.get(child) <= hole.get(child + 1)) as usize;
+                if hole.element() >= hole.get(child) {
+                    return;
+                }
+                hole.move_to(child);
+            }
+            child = 2 * hole.pos() + 1;
+        }
+
+        // SAFETY: child == end - 1 is in bounds and differs from the hole
+        unsafe {
+            if child == end - 1 && hole.element() < hole.get(child) {
+                hole.move_to(child);
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `pos` must be less than `self.len()`.
+    unsafe fn sift_down(&mut self, pos: usize) {
+        let len = self.len();
+        // SAFETY: the caller guarantees that pos < len
+        unsafe { self.sift_down_range(pos, len) };
+    }
+
+    /// Moves the element at `pos` all the way down to a leaf and then back up to its place.
+    /// The element came from the bottom of the heap, so this is faster than a plain sift down
+    /// on average.
+    ///
+    /// # Safety
+    ///
+    /// `pos` must be less than `self.len()`.
+    unsafe fn sift_down_to_bottom(&mut self, mut pos: usize) {
+        let end = self.len();
+        let start = pos;
+
+        // SAFETY: the caller guarantees that pos < len
+        let mut hole = unsafe { Hole::new(&mut self.data, pos) };
+        let mut child = 2 * hole.pos() + 1;
+
+        while child <= end.saturating_sub(2) {
+            // SAFETY: child and child + 1 are below end and differ from the hole
+            unsafe {
+                child += (hole.get(child) <= hole.get(child + 1)) as usize;
+                hole.move_to(child);
+            }
+            child = 2 * hole.pos() + 1;
+        }
+
+        if child == end - 1 {
+            // SAFETY: child == end - 1 is in bounds and differs from the hole
+            unsafe { hole.move_to(child) };
+        }
+        pos = hole.pos();
+        drop(hole);
+
+        // SAFETY: pos is the hole's last position, which is in bounds
+        unsafe { self.sift_up(start, pos) };
+    }
+
+    /// Restores the heap order after elements were appended from `start` on, either by
+    /// sifting each of them up or by rebuilding the whole heap, whichever is cheaper.
+    fn rebuild_tail(&mut self, start: usize) {
+        if start == self.len() {
+            return;
+        }
+
+        let tail_len = self.len() - start;
+        // rebuilding costs about 2 * len comparisons, sifting up about log2(start) per element
+        let better_to_rebuild = if start < tail_len {
+            true
+        } else if self.len() <= 2048 {
+            2 * self.len() < tail_len * start.ilog2() as usize
+        } else {
+            2 * self.len() < tail_len * 11
+        };
+
+        if better_to_rebuild {
+            self.rebuild();
+        } else {
+            for i in start..self.len() {
+                // SAFETY: i is in start..len
+                unsafe { self.sift_up(0, i) };
+            }
+        }
+    }
+
+    /// Heapifies the whole vector bottom-up in O(n).
+    fn rebuild(&mut self) {
+        let mut n = self.len() / 2;
+        while n > 0 {
+            n -= 1;
+            // SAFETY: n < len / 2
+            unsafe { self.sift_down(n) };
+        }
+    }
+}
+
+impl<T> BinaryHeap<T> {
+    /// Returns the greatest element, or `None` if the heap is empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.data.first()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.data.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.data.shrink_to_fit();
+    }
+
+    /// Returns an iterator over the elements in no particular order.
+    pub fn iter(&self) -> Iter<'_, T> {
+        self.data.iter()
+    }
+
+    /// Returns the underlying slice, in no particular order.
+    pub fn as_slice(&self) -> &[T] {
+        self.data.as_slice()
+    }
+
+    /// Removes all elements, yielding them in no particular order.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        self.data.drain(..)
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+    }
+
+    /// Returns the underlying vector, in no particular order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+}
+
+/// A position in the heap whose element has been moved out, so other elements can be shifted
+/// into it without swapping. The element is written back at the final position on drop.
+struct Hole<'a, T: 'a> {
+    data: &'a mut [T],
+    element: ManuallyDrop<T>,
+    pos: usize,
+}
+
+impl<'a, T> Hole<'a, T> {
+    /// # Safety
+    ///
+    /// `pos` must be in bounds of `data`.
+    #[inline]
+    unsafe fn new(data: &'a mut [T], pos: usize) -> Self {
+        debug_assert!(pos < data.len());
+        // SAFETY: pos is in bounds, and the hole writes the element back on drop
+        let element = unsafe { ptr::read(data.get_unchecked(pos)) };
+        Hole {
+            data,
+            element: ManuallyDrop::new(element),
+            pos,
+        }
+    }
+
+    #[inline]
+    fn pos(&self) -> usize {
+        self.pos
+    }
+
+    #[inline]
+    fn element(&self) -> &T {
+        &self.element
+    }
+
+    /// # Safety
+    ///
+    /// `index` must be in bounds and differ from the hole's position.
+    #[inline]
+    unsafe fn get(&self, index: usize) -> &T {
+        debug_assert!(index != self.pos);
+        debug_assert!(index < self.data.len());
+        // SAFETY: guaranteed by the caller
+        unsafe { self.data.get_unchecked(index) }
+    }
+
+    /// Moves the element at `index` into the hole, which then moves to `index`.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be in bounds and differ from the hole's position.
+    #[inline]
+    unsafe fn move_to(&mut self, index: usize) {
+        debug_assert!(index != self.pos);
+        debug_assert!(index < self.data.len());
+        // SAFETY: both positions are in bounds and distinct
+        unsafe {
+            let ptr = self.data.as_mut_ptr();
+            ptr::copy_nonoverlapping(ptr.add(index), ptr.add(self.pos), 1);
+        }
+        self.pos = index;
+    }
+}
+
+impl<T> Drop for Hole<'_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        // SAFETY: the hole's position is in bounds and its element was moved out
+        unsafe {
+            let pos = self.pos;
+            ptr::copy_nonoverlapping(&*self.element, self.data.get_unchecked_mut(pos), 1);
+        }
+    }
+}
+
+/// Mutable access to the greatest element of a `BinaryHeap`, see [`BinaryHeap::peek_mut`].
+pub struct PeekMut<'a, T: 'a + Ord> {
+    heap: &'a mut BinaryHeap<T>,
+    // set once the element may have been changed; the heap is shortened to just the root
+    // until the drop sifts it, so leaking the guard can't leave the heap out of order
+    original_len: Option<NonZeroUsize>,
+}
+
+impl<T: Ord> PeekMut<'_, T> {
+    /// Removes the peeked element from the heap and returns it.
+    pub fn pop(mut this: Self) -> T {
+        if let Some(original_len) = this.original_len.take() {
+            // SAFETY: the length was only shortened, the elements past it are still there
+            unsafe { this.heap.data.set_len(original_len.get()) };
+        }
+        this.heap.pop().unwrap()
+    }
+}
+
+impl<T: Ord> Deref for PeekMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.heap.data[0]
+    }
+}
+
+impl<T: Ord> DerefMut for PeekMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        let len = self.heap.len();
+        if len > 1 && self.original_len.is_none() {
+            self.original_len = NonZeroUsize::new(len);
+            // SAFETY: 1 is in bounds, and the drop restores the length
+            unsafe { self.heap.data.set_len(1) };
+        }
+        &mut self.heap.data[0]
+    }
+}
+
+impl<T: Ord> Drop for PeekMut<'_, T> {
+    fn drop(&mut self) {
+        if let Some(original_len) = self.original_len {
+            // SAFETY: the length was only shortened, and a PeekMut is only created for a
+            // non-empty heap
+            unsafe {
+                self.heap.data.set_len(original_len.get());
+                self.heap.sift_down(0);
+            }
+        }
+    }
+}
+
+impl<T: Ord + Debug> Debug for PeekMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PeekMut").field(&self.heap.data[0]).finish()
+    }
+}
+
+impl<T: Ord> Queue<T> for BinaryHeap<T> {
+    type Iter<'a>
+        = Iter<'a, T>
+    where
+        T: 'a;
+
+    type PeekMut<'a>
+        = PeekMut<'a, T>
+    where
+        T: 'a;
+
+    fn add(&mut self, element: T) -> Result<(), QueueError> {
+        self.push(element);
+        Ok(())
+    }
+
+    /// Removes the greatest element.
+    fn remove(&mut self) -> Result<T, QueueError> {
+        self.pop().ok_or(QueueError::EmptyQueue)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    fn peek_mut(&mut self) -> Option<PeekMut<'_, T>> {
+        self.peek_mut()
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+}
+
+impl<T: Ord> Default for BinaryHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for BinaryHeap<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for BinaryHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+/// Heapifies the vector in place in O(n).
+impl<T: Ord> From<Vec<T>> for BinaryHeap<T> {
+    fn from(vec: Vec<T>) -> Self {
+        let mut heap = BinaryHeap { data: vec };
+        heap.rebuild();
+        heap
+    }
+}
+
+impl<T> From<BinaryHeap<T>> for Vec<T> {
+    fn from(heap: BinaryHeap<T>) -> Self {
+        heap.data
+    }
+}
+
+impl<T: Ord> FromIterator<T> for BinaryHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        BinaryHeap::from(iter.into_iter().collect::<Vec<T>>())
+    }
+}
+
+impl<T: Ord> Extend<T> for BinaryHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let start = self.len();
+        self.data.extend(iter);
+        self.rebuild_tail(start);
+    }
+}
+
+impl<T> IntoIterator for BinaryHeap<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Yields the elements in no particular order.
+    fn into_iter(self) -> IntoIter<T> {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a BinaryHeap<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+/// Iterator over the elements of a `MinHeap`, in no particular order.
+pub type MinIter<'a, T> = Map<Iter<'a, Reverse<T>>, fn(&Reverse<T>) -> &T>;
+
+/// A priority queue that pops the smallest element first, a thin wrapper around a
+/// `BinaryHeap` of [`Reverse`] elements.
+pub struct MinHeap<T> {
+    heap: BinaryHeap<Reverse<T>>,
+}
+
+impl<T: Ord> MinHeap<T> {
+    pub const fn new() -> Self {
+        Self {
+            heap: BinaryHeap::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            heap: BinaryHeap::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.heap.push(Reverse(item));
+    }
+
+    /// Removes the smallest element, or returns `None` if the heap is empty.
+    pub fn pop(&mut self) -> Option<T> {
+        self.heap.pop().map(|Reverse(item)| item)
+    }
+
+    /// Returns a guard to the smallest element, see [`BinaryHeap::peek_mut`].
+    pub fn peek_mut(&mut self) -> Option<MinPeekMut<'_, T>> {
+        self.heap.peek_mut().map(MinPeekMut)
+    }
+
+    /// Returns the elements in descending order, the reverse of the order they'd be popped in.
+    pub fn into_sorted_vec(self) -> Vec<T> {
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse(item)| item)
+            .collect()
+    }
+
+    pub fn append(&mut self, other: &mut Self) {
+        self.heap.append(&mut other.heap);
+    }
+}
+
+impl<T> MinHeap<T> {
+    /// Returns the smallest element, or `None` if the heap is empty.
+    pub fn peek(&self) -> Option<&T> {
+        self.heap.peek().map(|Reverse(item)| item)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.heap.capacity()
+    }
+
+    pub fn clear(&mut self) {
+        self.heap.clear();
+    }
+
+    /// Returns an iterator over the elements in no particular order.
+    pub fn iter(&self) -> MinIter<'_, T> {
+        self.heap.iter().map(|Reverse(item)| item)
+    }
+
+    /// Returns the underlying vector, in no particular order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.heap
+            .into_vec()
+            .into_iter()
+            .map(|Reverse(item)| item)
+            .collect()
+    }
+}
+
+/// Mutable access to the smallest element of a `MinHeap`, see [`MinHeap::peek_mut`].
+pub struct MinPeekMut<'a, T: 'a + Ord>(PeekMut<'a, Reverse<T>>);
+
+impl<T: Ord> MinPeekMut<'_, T> {
+    /// Removes the peeked element from the heap and returns it.
+    pub fn pop(this: Self) -> T {
+        PeekMut::pop(this.0).0
+    }
+}
+
+impl<T: Ord> Deref for MinPeekMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0 .0
+    }
+}
+
+impl<T: Ord> DerefMut for MinPeekMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0 .0
+    }
+}
+
+impl<T: Ord + Debug> Debug for MinPeekMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("MinPeekMut").field(&**self).finish()
+    }
+}
+
+impl<T: Ord> Queue<T> for MinHeap<T> {
+    type Iter<'a>
+        = MinIter<'a, T>
+    where
+        T: 'a;
+
+    type PeekMut<'a>
+        = MinPeekMut<'a, T>
+    where
+        T: 'a;
+
+    fn add(&mut self, element: T) -> Result<(), QueueError> {
+        self.push(element);
+        Ok(())
+    }
+
+    /// Removes the smallest element.
+    fn remove(&mut self) -> Result<T, QueueError> {
+        self.pop().ok_or(QueueError::EmptyQueue)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.peek()
+    }
+
+    fn peek_mut(&mut self) -> Option<MinPeekMut<'_, T>> {
+        self.peek_mut()
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn iter(&self) -> MinIter<'_, T> {
+        self.iter()
+    }
+}
+
+impl<T: Ord> Default for MinHeap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Clone for MinHeap<T> {
+    fn clone(&self) -> Self {
+        Self {
+            heap: self.heap.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for MinHeap<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Ord> From<Vec<T>> for MinHeap<T> {
+    fn from(vec: Vec<T>) -> Self {
+        vec.into_iter().collect()
+    }
+}
+
+impl<T: Ord> FromIterator<T> for MinHeap<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self {
+            heap: iter.into_iter().map(Reverse).collect(),
+        }
+    }
+}
+
+impl<T: Ord> Extend<T> for MinHeap<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.heap.extend(iter.into_iter().map(Reverse));
+    }
+}
@@ -0,0 +1,283 @@
+use std::fmt::{self, Debug};
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
+
+use super::vecdeque::{self, VecDeque};
+use super::{Queue, QueueError};
+
+/// A FIFO queue that holds at most `capacity` elements and never grows past that.
+pub struct BoundedQueue<T> {
+    deque: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> BoundedQueue<T> {
+    /// Creates an empty queue, allocating room for all `capacity` elements up front.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero. Such a queue would always be full, so a blocked producer
+    /// and consumer would wait on each other forever.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be non-zero");
+        Self {
+            deque: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.deque.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.deque.is_empty()
+    }
+
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.deque.len() == self.capacity
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Appends an element to the back, or hands it back if the queue is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.deque.push_back(value);
+        Ok(())
+    }
+
+    #[inline]
+    pub fn pop(&mut self) -> Option<T> {
+        self.deque.pop_front()
+    }
+
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.deque.front()
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.deque.clear()
+    }
+
+    #[inline]
+    pub fn iter(&self) -> vecdeque::Iter<'_, T> {
+        self.deque.iter()
+    }
+}
+
+impl<T> Queue<T> for BoundedQueue<T> {
+    type Iter<'a>
+        = vecdeque::Iter<'a, T>
+    where
+        T: 'a;
+
+    type PeekMut<'a>
+        = &'a mut T
+    where
+        T: 'a;
+
+    /// Returns `FullQueue` if the queue is at capacity, dropping the element.
+    fn add(&mut self, element: T) -> Result<(), QueueError> {
+        self.push(element).map_err(|_| QueueError::FullQueue)
+    }
+
+    fn remove(&mut self) -> Result<T, QueueError> {
+        self.pop().ok_or(QueueError::EmptyQueue)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.front()
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        self.deque.front_mut()
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.capacity)
+    }
+
+    fn iter(&self) -> vecdeque::Iter<'_, T> {
+        self.iter()
+    }
+}
+
+impl<T: Clone> Clone for BoundedQueue<T> {
+    fn clone(&self) -> Self {
+        let mut queue = BoundedQueue::new(self.capacity);
+        queue.deque.extend(self.iter().cloned());
+        queue
+    }
+}
+
+impl<T: Debug> Debug for BoundedQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoundedQueue")
+            .field("elements", &self.deque)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+/// A `BoundedQueue` shared between threads. Producers can wait for room and consumers can
+/// wait for elements, which gives back-pressure when the consumers fall behind.
+pub struct SyncBoundedQueue<T> {
+    queue: Mutex<BoundedQueue<T>>,
+    not_empty: Condvar,
+    not_full: Condvar,
+}
+
+impl<T> SyncBoundedQueue<T> {
+    /// Creates an empty queue.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is zero, see [`BoundedQueue::new`].
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: Mutex::new(BoundedQueue::new(capacity)),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+        }
+    }
+
+    /// Locks the queue. A thread that panicked while holding the lock can't have left the
+    /// queue half-updated, so poisoning is ignored.
+    fn lock(&self) -> MutexGuard<'_, BoundedQueue<T>> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.lock().capacity()
+    }
+
+    /// Appends an element without waiting, or returns `FullQueue` if there is no room.
+    pub fn add(&self, element: T) -> Result<(), QueueError> {
+        self.lock().add(element)?;
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Removes the front element without waiting, or returns `EmptyQueue` if there is none.
+    pub fn remove(&self) -> Result<T, QueueError> {
+        let element = self.lock().remove()?;
+        self.not_full.notify_one();
+        Ok(element)
+    }
+
+    /// Appends an element, waiting as long as it takes for room.
+    pub fn add_blocking(&self, element: T) {
+        let mut queue = self.lock();
+        while queue.is_full() {
+            queue = self
+                .not_full
+                .wait(queue)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        let _ = queue.push(element);
+        drop(queue);
+        self.not_empty.notify_one();
+    }
+
+    /// Removes the front element, waiting as long as it takes for one.
+    pub fn remove_blocking(&self) -> T {
+        let mut queue = self.lock();
+        loop {
+            if let Some(element) = queue.pop() {
+                drop(queue);
+                self.not_full.notify_one();
+                return element;
+            }
+            queue = self
+                .not_empty
+                .wait(queue)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Appends an element, waiting up to `timeout` for room. Hands the element back if the
+    /// queue is still full by then, so a producer that gives up doesn't lose it.
+    pub fn add_timeout(&self, element: T, timeout: Duration) -> Result<(), T> {
+        let deadline = Instant::now().checked_add(timeout);
+        let mut queue = self.lock();
+        while queue.is_full() {
+            let remaining = match remaining(deadline) {
+                Some(remaining) => remaining,
+                None => return Err(element),
+            };
+            queue = self
+                .not_full
+                .wait_timeout(queue, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        let _ = queue.push(element);
+        drop(queue);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+
+    /// Removes the front element, waiting up to `timeout` for one. Returns `EmptyQueue` if
+    /// the queue is still empty by then.
+    pub fn remove_timeout(&self, timeout: Duration) -> Result<T, QueueError> {
+        let deadline = Instant::now().checked_add(timeout);
+        let mut queue = self.lock();
+        loop {
+            if let Some(element) = queue.pop() {
+                drop(queue);
+                self.not_full.notify_one();
+                return Ok(element);
+            }
+            let remaining = match remaining(deadline) {
+                Some(remaining) => remaining,
+                None => return Err(QueueError::EmptyQueue),
+            };
+            queue = self
+                .not_empty
+                .wait_timeout(queue, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+}
+
+/// Returns how long to wait until `deadline`, or `None` once it has passed. A deadline too
+/// far away to represent means waiting indefinitely, in steps of a day.
+fn remaining(deadline: Option<Instant>) -> Option<Duration> {
+    const FOREVER: Duration = Duration::from_secs(24 * 60 * 60);
+    match deadline {
+        Some(deadline) => deadline
+            .checked_duration_since(Instant::now())
+            .filter(|remaining| !remaining.is_zero()),
+        None => Some(FOREVER),
+    }
+}
+
+impl<T: Debug> Debug for SyncBoundedQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SyncBoundedQueue")
+            .field(&*self.lock())
+            .finish()
+    }
+}
@@ -1,12 +1,171 @@
+pub mod binary_heap;
+pub mod bounded;
+pub mod mpmc;
+mod padded;
+pub mod spsc;
+pub mod vecdeque;
+
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ops::DerefMut;
+
+pub use binary_heap::{BinaryHeap, MinHeap, MinPeekMut, PeekMut};
+pub use bounded::{BoundedQueue, SyncBoundedQueue};
+pub use mpmc::MpmcQueue;
+pub use spsc::{channel, Consumer, Producer, SpscQueue};
+pub use vecdeque::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum QueueError {
     EmptyQueue,
     FullQueue,
 }
 
+impl Display for QueueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueueError::EmptyQueue => f.write_str("queue is empty"),
+            QueueError::FullQueue => f.write_str("queue is full"),
+        }
+    }
+}
+
+impl Error for QueueError {}
+
 pub trait Queue<E> {
+    /// Iterator over the elements, in the order the container stores them.
+    type Iter<'a>: Iterator<Item = &'a E>
+    where
+        Self: 'a,
+        E: 'a;
+
+    /// Mutable access to the next element. Priority queues use a guard that restores their
+    /// order on drop.
+    type PeekMut<'a>: DerefMut<Target = E>
+    where
+        Self: 'a;
+
     fn add(&mut self, element: E) -> Result<(), QueueError>;
 
     fn remove(&mut self) -> Result<E, QueueError>;
 
-    fn peek(&self) -> Result<Option<&E>, QueueError>;
+    /// Returns the element `remove` would return next.
+    fn peek(&self) -> Option<&E>;
+
+    fn peek_mut(&mut self) -> Option<Self::PeekMut<'_>>;
+
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The most elements the queue can hold, or `None` if it grows as needed.
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    fn iter(&self) -> Self::Iter<'_>;
+
+    /// Removes all elements, yielding them in the order `remove` would.
+    fn drain(&mut self) -> Drain<'_, E, Self> {
+        Drain {
+            queue: self,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Forwards `Queue` through owning pointers, so that a boxed queue can be used wherever a
+/// queue is expected. Generic code that only borrows a queue should take `&mut Q` with
+/// `Q: Queue<E> + ?Sized` instead.
+macro_rules! forward_queue {
+    ($($ty:ty),*) => {$(
+        impl<E, Q: Queue<E> + ?Sized> Queue<E> for $ty {
+            type Iter<'a>
+                = Q::Iter<'a>
+            where
+                Self: 'a,
+                E: 'a;
+
+            type PeekMut<'a>
+                = Q::PeekMut<'a>
+            where
+                Self: 'a;
+
+            fn add(&mut self, element: E) -> Result<(), QueueError> {
+                (**self).add(element)
+            }
+
+            fn remove(&mut self) -> Result<E, QueueError> {
+                (**self).remove()
+            }
+
+            fn peek(&self) -> Option<&E> {
+                (**self).peek()
+            }
+
+            fn peek_mut(&mut self) -> Option<Q::PeekMut<'_>> {
+                (**self).peek_mut()
+            }
+
+            fn len(&self) -> usize {
+                (**self).len()
+            }
+
+            fn is_empty(&self) -> bool {
+                (**self).is_empty()
+            }
+
+            fn capacity(&self) -> Option<usize> {
+                (**self).capacity()
+            }
+
+            fn iter(&self) -> Q::Iter<'_> {
+                (**self).iter()
+            }
+        }
+    )*};
+}
+
+forward_queue!(Box<Q>, crate::pointer::Box<Q>);
+
+/// Removes elements from a queue one at a time, see [`Queue::drain`]. Whatever is left when
+/// it is dropped is removed as well.
+pub struct Drain<'a, E, Q: Queue<E> + ?Sized> {
+    queue: &'a mut Q,
+    marker: PhantomData<E>,
+}
+
+impl<E, Q: Queue<E> + ?Sized> Iterator for Drain<'_, E, Q> {
+    type Item = E;
+
+    fn next(&mut self) -> Option<E> {
+        self.queue.remove().ok()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.queue.len();
+        (len, Some(len))
+    }
+}
+
+impl<E, Q: Queue<E> + ?Sized> ExactSizeIterator for Drain<'_, E, Q> {}
+
+impl<E, Q: Queue<E> + ?Sized> FusedIterator for Drain<'_, E, Q> {}
+
+impl<E, Q: Queue<E> + ?Sized> Drop for Drain<'_, E, Q> {
+    fn drop(&mut self) {
+        while self.next().is_some() {}
+    }
+}
+
+impl<E, Q: Queue<E> + ?Sized> Debug for Drain<'_, E, Q> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Drain")
+            .field("len", &self.queue.len())
+            .finish()
+    }
 }
@@ -0,0 +1,305 @@
+//! A bounded multi-producer/multi-consumer queue, after Dmitry Vyukov's design.
+//!
+//! Every slot carries a sequence number telling whether it is ready to be written or read
+//! for a given lap around the buffer. Producers and consumers claim a position with a single
+//! CAS and then hand the slot over by bumping its sequence, so there is no lock, though a
+//! thread stalled between the two steps holds up that one slot.
+//!
+//! Another consumer could take the front element at any moment, so `peek` takes `&mut self`,
+//! and the [`Queue`] impl lives on the view [`MpmcQueue::exclusive`] returns.
+
+use std::cell::UnsafeCell;
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use super::padded::CachePadded;
+use super::{Queue, QueueError};
+use crate::vector::Vec;
+
+struct Slot<T> {
+    sequence: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+pub struct MpmcQueue<T> {
+    buffer: Vec<Slot<T>>,
+    mask: usize,
+    enqueue_pos: CachePadded<AtomicUsize>,
+    dequeue_pos: CachePadded<AtomicUsize>,
+}
+
+// SAFETY: a slot's value is only accessed by the thread that claimed its position, and the
+// sequence number hands it over with Acquire/Release, so elements only need to be `Send`
+unsafe impl<T: Send> Send for MpmcQueue<T> {}
+unsafe impl<T: Send> Sync for MpmcQueue<T> {}
+
+impl<T> MpmcQueue<T> {
+    /// Creates an empty queue. The capacity is rounded up to a power of two, and to at least 2.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be non-zero");
+        let capacity = capacity
+            .max(2)
+            .checked_next_power_of_two()
+            .expect("capacity overflow");
+        let buffer = (0..capacity)
+            .map(|i| Slot {
+                sequence: AtomicUsize::new(i),
+                value: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect();
+        Self {
+            buffer,
+            mask: capacity - 1,
+            enqueue_pos: CachePadded(AtomicUsize::new(0)),
+            dequeue_pos: CachePadded(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Appends an element to the back, or hands it back if the queue is full.
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let mut pos = self.enqueue_pos.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.buffer[pos & self.mask];
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let diff = sequence.wrapping_sub(pos) as isize;
+
+            if diff == 0 {
+                // the slot is free for this lap, try to claim the position
+                match self.enqueue_pos.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY: claiming `pos` gives exclusive access to the slot until its
+                        // sequence is bumped
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.sequence.store(pos.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                // the slot still holds the element from the previous lap
+                return Err(value);
+            } else {
+                pos = self.enqueue_pos.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Removes the front element, or returns `None` if the queue is empty.
+    pub fn pop(&self) -> Option<T> {
+        let mut pos = self.dequeue_pos.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.buffer[pos & self.mask];
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            let diff = sequence.wrapping_sub(pos.wrapping_add(1)) as isize;
+
+            if diff == 0 {
+                match self.dequeue_pos.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // SAFETY: the producer of this lap initialized the slot, and claiming
+                        // `pos` gives exclusive access until the sequence is bumped
+                        let value = unsafe { (*slot.value.get()).assume_init_read() };
+                        slot.sequence
+                            .store(pos.wrapping_add(self.mask + 1), Ordering::Release);
+                        return Some(value);
+                    }
+                    Err(current) => pos = current,
+                }
+            } else if diff < 0 {
+                // nothing has been written to the slot for this lap yet
+                return None;
+            } else {
+                pos = self.dequeue_pos.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Returns `FullQueue` if the queue is at capacity, dropping the element.
+    pub fn add(&self, element: T) -> Result<(), QueueError> {
+        self.push(element).map_err(|_| QueueError::FullQueue)
+    }
+
+    pub fn remove(&self) -> Result<T, QueueError> {
+        self.pop().ok_or(QueueError::EmptyQueue)
+    }
+
+    /// Number of elements in the queue. Other threads may be adding or removing elements
+    /// concurrently, so the result is only a snapshot.
+    pub fn len(&self) -> usize {
+        loop {
+            let enqueue = self.enqueue_pos.load(Ordering::Acquire);
+            let dequeue = self.dequeue_pos.load(Ordering::Acquire);
+            // retry if a consumer moved past the enqueue position we read
+            if self.enqueue_pos.load(Ordering::Acquire) == enqueue {
+                return enqueue.wrapping_sub(dequeue).min(self.capacity());
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.mask + 1
+    }
+
+    /// Returns the front element. This takes `&mut self`, so no other thread can remove it
+    /// while it is borrowed.
+    pub fn peek(&mut self) -> Option<&T> {
+        self.front().map(|front| {
+            // SAFETY: the slot holds an element, and `&mut self` keeps other threads out
+            unsafe { (*front).assume_init_ref() }
+        })
+    }
+
+    /// Borrows the queue exclusively, as a view that implements [`Queue`].
+    pub fn exclusive(&mut self) -> Exclusive<'_, T> {
+        Exclusive {
+            queue: self,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the slot of the front element. Only meaningful while nothing else can push or
+    /// pop, so every claimed position has been written.
+    #[inline]
+    fn front(&self) -> Option<*mut MaybeUninit<T>> {
+        let pos = self.dequeue_pos.load(Ordering::Relaxed);
+        if pos == self.enqueue_pos.load(Ordering::Relaxed) {
+            None
+        } else {
+            Some(self.buffer[pos & self.mask].value.get())
+        }
+    }
+}
+
+/// Exclusive access to an [`MpmcQueue`], through which it implements [`Queue`]. Unlike the
+/// queue itself, the view doesn't let `&self` remove elements, so `peek` can borrow them.
+pub struct Exclusive<'a, T> {
+    queue: &'a mut MpmcQueue<T>,
+    // `peek` hands out `&T` through `&self`, so sharing the view needs `T: Sync`
+    marker: PhantomData<&'a T>,
+}
+
+// SAFETY: the view has exclusive access to the queue, so moving it to another thread is like
+// moving the elements
+unsafe impl<T: Send> Send for Exclusive<'_, T> {}
+
+impl<T> Queue<T> for Exclusive<'_, T> {
+    type Iter<'a>
+        = Iter<'a, T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    type PeekMut<'a>
+        = &'a mut T
+    where
+        Self: 'a;
+
+    /// Returns `FullQueue` if the queue is at capacity, dropping the element.
+    fn add(&mut self, element: T) -> Result<(), QueueError> {
+        self.queue.add(element)
+    }
+
+    fn remove(&mut self) -> Result<T, QueueError> {
+        self.queue.remove()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        // SAFETY: the slot holds an element, and only `remove` could take it out, which needs
+        // `&mut self`
+        self.queue
+            .front()
+            .map(|front| unsafe { (*front).assume_init_ref() })
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: as in `peek`, and `&mut self` makes the access unique
+        self.queue
+            .front()
+            .map(|front| unsafe { (*front).assume_init_mut() })
+    }
+
+    fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.queue.capacity())
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            queue: self.queue,
+            pos: self.queue.dequeue_pos.load(Ordering::Relaxed),
+            end: self.queue.enqueue_pos.load(Ordering::Relaxed),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Debug for Exclusive<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Exclusive").field(&self.queue).finish()
+    }
+}
+
+/// Iterator over the elements of an [`Exclusive`] view, from front to back.
+pub struct Iter<'a, T> {
+    queue: &'a MpmcQueue<T>,
+    pos: usize,
+    end: usize,
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.pos == self.end {
+            return None;
+        }
+        let slot = &self.queue.buffer[self.pos & self.queue.mask];
+        self.pos = self.pos.wrapping_add(1);
+        // SAFETY: the positions in `pos..end` were all written, and the view is borrowed for 'a
+        Some(unsafe { (*slot.value.get()).assume_init_ref() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end.wrapping_sub(self.pos);
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<T> Drop for MpmcQueue<T> {
+    fn drop(&mut self) {
+        while self.pop().is_some() {}
+    }
+}
+
+impl<T> Debug for MpmcQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MpmcQueue")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
@@ -0,0 +1,14 @@
+use std::ops::Deref;
+
+/// Aligns a value to its own cache line, so that atomics written by different threads don't
+/// keep invalidating each other's cache lines.
+#[repr(align(64))]
+pub(super) struct CachePadded<T>(pub(super) T);
+
+impl<T> Deref for CachePadded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
@@ -0,0 +1,347 @@
+//! A wait-free single-producer/single-consumer ring buffer.
+//!
+//! Each side keeps a cached copy of the other side's index and only reloads the shared atomic
+//! when the cache says the buffer is full (or empty), so most operations touch no cache line
+//! owned by the other thread.
+//!
+//! The halves only offer what is sound for their side. [`SpscQueue`] owns both of them and
+//! implements [`Queue`] for code that uses the ring buffer from a single place, and can be
+//! split once the halves should move to their own threads.
+
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+use std::marker::PhantomData;
+use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use super::padded::CachePadded;
+use super::{Queue, QueueError};
+use crate::pointer::Cell;
+use crate::vector::RawVec;
+
+struct Shared<T> {
+    buf: RawVec<T>,
+    /// Number of slots, one more than the capacity so that full and empty can be told apart.
+    slots: usize,
+    /// Next slot to read, only written by the consumer.
+    head: CachePadded<AtomicUsize>,
+    /// Next slot to write, only written by the producer.
+    tail: CachePadded<AtomicUsize>,
+}
+
+// SAFETY: slots are only ever accessed by one side at a time, handed over through `head` and
+// `tail`, so sharing the buffer only requires the elements themselves to be `Send`
+unsafe impl<T: Send> Send for Shared<T> {}
+unsafe impl<T: Send> Sync for Shared<T> {}
+
+impl<T> Shared<T> {
+    #[inline]
+    fn next(&self, index: usize) -> usize {
+        if index + 1 == self.slots {
+            0
+        } else {
+            index + 1
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        let head = self.head.load(Ordering::Acquire);
+        let tail = self.tail.load(Ordering::Acquire);
+        if tail >= head {
+            tail - head
+        } else {
+            self.slots - head + tail
+        }
+    }
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        let mut head = self.head.load(Ordering::Relaxed);
+        let tail = self.tail.load(Ordering::Relaxed);
+        while head != tail {
+            // SAFETY: both handles are gone, and the slots in `head..tail` hold elements that
+            // were written but never read
+            unsafe { ptr::drop_in_place(self.buf.ptr().add(head)) };
+            head = self.next(head);
+        }
+    }
+}
+
+/// The sending half of a ring buffer created by [`channel`].
+pub struct Producer<T> {
+    shared: Arc<Shared<T>>,
+    tail: Cell<usize>,
+    cached_head: Cell<usize>,
+}
+
+/// The receiving half of a ring buffer created by [`channel`].
+pub struct Consumer<T> {
+    shared: Arc<Shared<T>>,
+    head: Cell<usize>,
+    cached_tail: Cell<usize>,
+}
+
+/// Creates a ring buffer that holds up to `capacity` elements and returns its two halves.
+pub fn channel<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    let slots = capacity.checked_add(1).expect("capacity overflow");
+    let shared = Arc::new(Shared {
+        buf: RawVec::with_capacity(slots),
+        slots,
+        head: CachePadded(AtomicUsize::new(0)),
+        tail: CachePadded(AtomicUsize::new(0)),
+    });
+    let producer = Producer {
+        shared: Arc::clone(&shared),
+        tail: Cell::new(0),
+        cached_head: Cell::new(0),
+    };
+    let consumer = Consumer {
+        shared,
+        head: Cell::new(0),
+        cached_tail: Cell::new(0),
+    };
+    (producer, consumer)
+}
+
+impl<T> Producer<T> {
+    /// Appends an element to the back, or hands it back if the buffer is full.
+    pub fn push(&self, value: T) -> Result<(), T> {
+        let tail = self.tail.get();
+        let next = self.shared.next(tail);
+        if next == self.cached_head.get() {
+            self.cached_head
+                .set(self.shared.head.load(Ordering::Acquire));
+            if next == self.cached_head.get() {
+                return Err(value);
+            }
+        }
+
+        // SAFETY: the slot is in bounds and the consumer has released it, so nobody else
+        // touches it until `tail` is published
+        unsafe { ptr::write(self.shared.buf.ptr().add(tail), value) };
+        self.shared.tail.store(next, Ordering::Release);
+        self.tail.set(next);
+        Ok(())
+    }
+
+    /// Returns `FullQueue` if the buffer is at capacity, dropping the element.
+    pub fn add(&self, element: T) -> Result<(), QueueError> {
+        self.push(element).map_err(|_| QueueError::FullQueue)
+    }
+
+    /// Number of elements in the buffer. The consumer may be removing elements concurrently,
+    /// so the result is only a snapshot.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.slots - 1
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Refreshes the cached tail if the buffer looks empty, and returns whether it still is.
+    #[inline]
+    fn check_empty(&self) -> bool {
+        let head = self.head.get();
+        if head == self.cached_tail.get() {
+            self.cached_tail
+                .set(self.shared.tail.load(Ordering::Acquire));
+        }
+        head == self.cached_tail.get()
+    }
+
+    /// Removes the front element, or returns `None` if the buffer is empty.
+    pub fn pop(&self) -> Option<T> {
+        if self.check_empty() {
+            return None;
+        }
+
+        let head = self.head.get();
+        // SAFETY: the producer published this slot through `tail` and won't reuse it until
+        // `head` moves past it
+        let value = unsafe { ptr::read(self.shared.buf.ptr().add(head)) };
+        let next = self.shared.next(head);
+        self.shared.head.store(next, Ordering::Release);
+        self.head.set(next);
+        Some(value)
+    }
+
+    pub fn remove(&self) -> Result<T, QueueError> {
+        self.pop().ok_or(QueueError::EmptyQueue)
+    }
+
+    /// Returns the front element. This takes `&mut self`, since `pop` only needs `&self` and
+    /// could otherwise move the element out while it is borrowed. [`SpscQueue`] peeks through
+    /// `&self` instead, because it doesn't hand out `pop`.
+    pub fn peek(&mut self) -> Option<&T> {
+        if self.check_empty() {
+            return None;
+        }
+        // SAFETY: same as in `pop`, and the slot stays valid until the next `pop`
+        Some(unsafe { &*self.shared.buf.ptr().add(self.head.get()) })
+    }
+
+    /// Number of elements in the buffer. The producer may be adding elements concurrently,
+    /// so the result is only a snapshot.
+    pub fn len(&self) -> usize {
+        self.shared.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.check_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.shared.slots - 1
+    }
+}
+
+/// A ring buffer that owns both of its halves. Since nothing else can remove elements,
+/// `peek` and `iter` can borrow them through `&self`.
+pub struct SpscQueue<T> {
+    producer: Producer<T>,
+    consumer: Consumer<T>,
+}
+
+impl<T> SpscQueue<T> {
+    pub fn new(capacity: usize) -> Self {
+        let (producer, consumer) = channel(capacity);
+        Self { producer, consumer }
+    }
+
+    /// Splits the queue into its halves, keeping the elements it holds.
+    pub fn split(self) -> (Producer<T>, Consumer<T>) {
+        (self.producer, self.consumer)
+    }
+
+    #[inline]
+    fn front(&self) -> Option<*mut T> {
+        let head = self.consumer.head.get();
+        if head == self.producer.tail.get() {
+            None
+        } else {
+            Some(self.consumer.shared.buf.ptr().wrapping_add(head))
+        }
+    }
+}
+
+impl<T> Queue<T> for SpscQueue<T> {
+    type Iter<'a>
+        = Iter<'a, T>
+    where
+        T: 'a;
+
+    type PeekMut<'a>
+        = &'a mut T
+    where
+        T: 'a;
+
+    /// Returns `FullQueue` if the buffer is at capacity, dropping the element.
+    fn add(&mut self, element: T) -> Result<(), QueueError> {
+        self.producer.add(element)
+    }
+
+    fn remove(&mut self) -> Result<T, QueueError> {
+        self.consumer.remove()
+    }
+
+    fn peek(&self) -> Option<&T> {
+        // SAFETY: the slot holds an element, and only `remove` could take it out, which needs
+        // `&mut self`
+        self.front().map(|front| unsafe { &*front })
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        // SAFETY: as in `peek`, and `&mut self` makes the access unique
+        self.front().map(|front| unsafe { &mut *front })
+    }
+
+    fn len(&self) -> usize {
+        self.producer.len()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        Some(self.producer.capacity())
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            shared: &self.consumer.shared,
+            head: self.consumer.head.get(),
+            tail: self.producer.tail.get(),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Iterator over the elements of an [`SpscQueue`], from front to back.
+pub struct Iter<'a, T> {
+    shared: &'a Shared<T>,
+    head: usize,
+    tail: usize,
+    // yields `&T`, so sending the iterator needs `T: Sync` like a slice iterator does
+    marker: PhantomData<&'a T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.head == self.tail {
+            return None;
+        }
+        // SAFETY: the slots in `head..tail` hold elements, and the queue is borrowed for 'a
+        let value = unsafe { &*self.shared.buf.ptr().add(self.head) };
+        self.head = self.shared.next(self.head);
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.tail >= self.head {
+            self.tail - self.head
+        } else {
+            self.shared.slots - self.head + self.tail
+        };
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<T> Debug for SpscQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpscQueue")
+            .field("len", &self.producer.len())
+            .field("capacity", &self.producer.capacity())
+            .finish()
+    }
+}
+
+impl<T> Debug for Producer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Producer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<T> Debug for Consumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
@@ -0,0 +1,779 @@
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+use std::mem::{self, ManuallyDrop};
+use std::ops::{Index, IndexMut};
+use std::{ptr, slice};
+
+use super::{Queue, QueueError};
+use crate::vector::{RawVec, TryReserveError, Vec};
+
+/// A growable ring buffer. The elements are the `len` slots starting at `head`, wrapping
+/// around to the start of the buffer.
+pub struct VecDeque<T> {
+    head: usize,
+    len: usize,
+    buf: RawVec<T>,
+}
+
+impl<T> VecDeque<T> {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            head: 0,
+            len: 0,
+            buf: RawVec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            head: 0,
+            len: 0,
+            buf: RawVec::with_capacity(capacity),
+        }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    #[inline]
+    fn ptr(&self) -> *mut T {
+        self.buf.ptr()
+    }
+
+    #[inline]
+    fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Returns `true` if the elements don't wrap around the end of the buffer.
+    #[inline]
+    fn is_contiguous(&self) -> bool {
+        self.head <= self.capacity() - self.len
+    }
+
+    #[inline]
+    fn wrap_add(&self, idx: usize, addend: usize) -> usize {
+        wrap_index(idx.wrapping_add(addend), self.capacity())
+    }
+
+    #[inline]
+    fn wrap_sub(&self, idx: usize, subtrahend: usize) -> usize {
+        wrap_index(
+            idx.wrapping_sub(subtrahend).wrapping_add(self.capacity()),
+            self.capacity(),
+        )
+    }
+
+    /// Maps an index into the deque to a slot in the buffer.
+    #[inline]
+    fn to_physical_idx(&self, idx: usize) -> usize {
+        self.wrap_add(self.head, idx)
+    }
+
+    /// # Safety
+    /// The slot at `off` must hold an element, which is moved out.
+    #[inline]
+    unsafe fn buffer_read(&self, off: usize) -> T {
+        unsafe { ptr::read(self.ptr().add(off)) }
+    }
+
+    /// # Safety
+    /// `off` must be within the buffer. An element already in the slot is overwritten.
+    #[inline]
+    unsafe fn buffer_write(&mut self, off: usize, value: T) {
+        unsafe { ptr::write(self.ptr().add(off), value) }
+    }
+
+    /// Moves `len` slots from `src` to `dst`, which may overlap.
+    #[inline]
+    unsafe fn copy(&self, src: usize, dst: usize, len: usize) {
+        unsafe { ptr::copy(self.ptr().add(src), self.ptr().add(dst), len) }
+    }
+
+    #[inline]
+    unsafe fn copy_nonoverlapping(&self, src: usize, dst: usize, len: usize) {
+        unsafe { ptr::copy_nonoverlapping(self.ptr().add(src), self.ptr().add(dst), len) }
+    }
+
+    /// Fixes up the elements after the buffer grew from `old_cap`, since a wrapped-around
+    /// deque now has a gap in the middle.
+    unsafe fn handle_capacity_increase(&mut self, old_cap: usize) {
+        let new_cap = self.capacity();
+        if self.head <= old_cap - self.len {
+            // A: [. . A B C D . .] is still contiguous, nothing to do
+            return;
+        }
+
+        let head_len = old_cap - self.head;
+        let tail_len = self.len - head_len;
+        unsafe {
+            if head_len > tail_len && new_cap - old_cap >= tail_len {
+                // B: [C D . A B] -> [. . . A B C D . .], move the short tail after the head
+                self.copy_nonoverlapping(0, old_cap, tail_len);
+            } else {
+                // C: [D . A B C] -> [D . . . . A B C], move the head to the end of the buffer
+                let new_head = new_cap - head_len;
+                self.copy(self.head, new_head, head_len);
+                self.head = new_head;
+            }
+        }
+    }
+
+    fn grow(&mut self) {
+        let old_cap = self.capacity();
+        self.buf.reserve_for_push(old_cap);
+        unsafe { self.handle_capacity_increase(old_cap) };
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        let old_cap = self.capacity();
+        if additional > old_cap - self.len {
+            self.buf.reserve(self.len, additional);
+            unsafe { self.handle_capacity_increase(old_cap) };
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let old_cap = self.capacity();
+        if additional > old_cap - self.len {
+            self.buf.try_reserve(self.len, additional)?;
+            unsafe { self.handle_capacity_increase(old_cap) };
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            let idx = self.to_physical_idx(index);
+            unsafe { Some(&*self.ptr().add(idx)) }
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            let idx = self.to_physical_idx(index);
+            unsafe { Some(&mut *self.ptr().add(idx)) }
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub fn front(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    #[inline]
+    pub fn front_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    #[inline]
+    pub fn back(&self) -> Option<&T> {
+        self.get(self.len.wrapping_sub(1))
+    }
+
+    #[inline]
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len.wrapping_sub(1))
+    }
+
+    pub fn push_back(&mut self, value: T) {
+        if self.is_full() {
+            self.grow();
+        }
+
+        let idx = self.to_physical_idx(self.len);
+        unsafe { self.buffer_write(idx, value) };
+        self.len += 1;
+    }
+
+    pub fn push_front(&mut self, value: T) {
+        if self.is_full() {
+            self.grow();
+        }
+
+        self.head = self.wrap_sub(self.head, 1);
+        self.len += 1;
+        unsafe { self.buffer_write(self.head, value) };
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let old_head = self.head;
+        self.head = self.to_physical_idx(1);
+        self.len -= 1;
+        unsafe { Some(self.buffer_read(old_head)) }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.len -= 1;
+        unsafe { Some(self.buffer_read(self.to_physical_idx(self.len))) }
+    }
+
+    /// Swaps the elements at indices `i` and `j`.
+    ///
+    /// # Panics
+    /// Panics if either index is out of bounds.
+    pub fn swap(&mut self, i: usize, j: usize) {
+        assert!(i < self.len, "index out of bounds");
+        assert!(j < self.len, "index out of bounds");
+        let ri = self.to_physical_idx(i);
+        let rj = self.to_physical_idx(j);
+        unsafe { ptr::swap(self.ptr().add(ri), self.ptr().add(rj)) }
+    }
+
+    /// Shortens the deque to `len` elements, dropping the rest from the back.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        let (front, back) = self.as_mut_slices();
+        let (drop_front, drop_back): (*mut [T], *mut [T]) = if len > front.len() {
+            let begin = len - front.len();
+            (&mut [][..], &mut back[begin..])
+        } else {
+            (&mut front[len..], back)
+        };
+        self.len = len;
+
+        // drops the back part even if dropping the front part panics
+        struct Dropper<T>(*mut [T]);
+
+        impl<T> Drop for Dropper<T> {
+            fn drop(&mut self) {
+                unsafe { ptr::drop_in_place(self.0) }
+            }
+        }
+
+        unsafe {
+            let _back_dropper = Dropper(drop_back);
+            ptr::drop_in_place(drop_front);
+        }
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        self.truncate(0);
+        // a fresh start keeps the next pushes contiguous
+        self.head = 0;
+    }
+
+    /// Returns the elements as two slices, the second of which holds the wrapped-around part.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let (first, second) = self.slice_ranges();
+        unsafe {
+            (
+                slice::from_raw_parts(self.ptr().add(first.0), first.1),
+                slice::from_raw_parts(self.ptr().add(second.0), second.1),
+            )
+        }
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (first, second) = self.slice_ranges();
+        unsafe {
+            (
+                slice::from_raw_parts_mut(self.ptr().add(first.0), first.1),
+                slice::from_raw_parts_mut(self.ptr().add(second.0), second.1),
+            )
+        }
+    }
+
+    /// Returns the `(start, len)` of the two parts of the buffer that hold the elements.
+    fn slice_ranges(&self) -> ((usize, usize), (usize, usize)) {
+        if self.is_contiguous() {
+            ((self.head, self.len), (0, 0))
+        } else {
+            let head_len = self.capacity() - self.head;
+            ((self.head, head_len), (0, self.len - head_len))
+        }
+    }
+
+    /// Rearranges the elements so that they don't wrap around, and returns them as one slice.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        if mem::size_of::<T>() == 0 {
+            self.head = 0;
+        }
+        if self.is_contiguous() {
+            return unsafe { slice::from_raw_parts_mut(self.ptr().add(self.head), self.len) };
+        }
+
+        let cap = self.capacity();
+        let free = cap - self.len;
+        let head_len = cap - self.head;
+        let tail_len = self.len - head_len;
+
+        unsafe {
+            if free >= head_len {
+                // [D E F G H . . . . A B C] -> [A B C D E F G H . . . .]
+                self.copy(0, head_len, tail_len);
+                self.copy_nonoverlapping(self.head, 0, head_len);
+                self.head = 0;
+            } else if free >= tail_len {
+                // [F G H . . . . A B C D E] -> [. . . A B C D E F G H .]
+                self.copy(self.head, tail_len, head_len);
+                self.copy_nonoverlapping(0, tail_len + head_len, tail_len);
+                self.head = tail_len;
+            } else {
+                // [E F G H . A B C D] -> [E F G H A B C D .] -> [A B C D E F G H .]
+                self.copy(self.head, tail_len, head_len);
+                slice::from_raw_parts_mut(self.ptr(), self.len).rotate_left(tail_len);
+                self.head = 0;
+            }
+            slice::from_raw_parts_mut(self.ptr().add(self.head), self.len)
+        }
+    }
+
+    /// Removes and returns the element at `index`, shifting whichever side is shorter.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if index >= self.len {
+            return None;
+        }
+
+        let wrapped_idx = self.to_physical_idx(index);
+        let element = unsafe { self.buffer_read(wrapped_idx) };
+
+        let k = self.len - index - 1;
+        // move each element one slot over, one at a time, so wrapping is handled uniformly
+        if k < index {
+            for i in index..self.len - 1 {
+                let src = self.to_physical_idx(i + 1);
+                let dst = self.to_physical_idx(i);
+                unsafe { self.copy(src, dst, 1) };
+            }
+        } else {
+            for i in (0..index).rev() {
+                let src = self.to_physical_idx(i);
+                let dst = self.to_physical_idx(i + 1);
+                unsafe { self.copy(src, dst, 1) };
+            }
+            self.head = self.to_physical_idx(1);
+        }
+        self.len -= 1;
+        Some(element)
+    }
+
+    /// Inserts an element at `index`, shifting whichever side is shorter.
+    ///
+    /// # Panics
+    /// Panics if `index > len`.
+    pub fn insert(&mut self, index: usize, value: T) {
+        assert!(index <= self.len, "index out of bounds");
+        if self.is_full() {
+            self.grow();
+        }
+
+        let k = self.len - index;
+        if k < index {
+            for i in (index..self.len).rev() {
+                let src = self.to_physical_idx(i);
+                let dst = self.to_physical_idx(i + 1);
+                unsafe { self.copy(src, dst, 1) };
+            }
+        } else {
+            self.head = self.wrap_sub(self.head, 1);
+            for i in 0..index {
+                let src = self.to_physical_idx(i + 1);
+                let dst = self.to_physical_idx(i);
+                unsafe { self.copy(src, dst, 1) };
+            }
+        }
+        let idx = self.to_physical_idx(index);
+        unsafe { self.buffer_write(idx, value) };
+        self.len += 1;
+    }
+
+    /// Shrinks the buffer as close to `len` as possible.
+    pub fn shrink_to_fit(&mut self) {
+        if self.len < self.capacity() && mem::size_of::<T>() != 0 {
+            self.make_contiguous();
+            unsafe {
+                if self.head != 0 {
+                    self.copy(self.head, 0, self.len);
+                    self.head = 0;
+                }
+            }
+            self.buf.shrink_to(self.len);
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        let (front, back) = self.as_slices();
+        Iter {
+            i1: front.iter(),
+            i2: back.iter(),
+        }
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        let (front, back) = self.as_mut_slices();
+        IterMut {
+            i1: front.iter_mut(),
+            i2: back.iter_mut(),
+        }
+    }
+}
+
+/// Wraps a logical slot that is at most one capacity past the end back into the buffer.
+#[inline]
+fn wrap_index(logical_index: usize, capacity: usize) -> usize {
+    debug_assert!(
+        (logical_index == 0 && capacity == 0)
+            || logical_index < capacity
+            || (logical_index - capacity) < capacity
+    );
+    if logical_index >= capacity {
+        logical_index - capacity
+    } else {
+        logical_index
+    }
+}
+
+impl<T> Queue<T> for VecDeque<T> {
+    type Iter<'a>
+        = Iter<'a, T>
+    where
+        T: 'a;
+
+    type PeekMut<'a>
+        = &'a mut T
+    where
+        T: 'a;
+
+    fn add(&mut self, element: T) -> Result<(), QueueError> {
+        self.push_back(element);
+        Ok(())
+    }
+
+    fn remove(&mut self) -> Result<T, QueueError> {
+        self.pop_front().ok_or(QueueError::EmptyQueue)
+    }
+
+    fn peek(&self) -> Option<&T> {
+        self.front()
+    }
+
+    fn peek_mut(&mut self) -> Option<&mut T> {
+        self.front_mut()
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn iter(&self) -> Iter<'_, T> {
+        self.iter()
+    }
+}
+
+impl<T> Index<usize> for VecDeque<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> IndexMut<usize> for VecDeque<T> {
+    #[inline]
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T> Default for VecDeque<T> {
+    #[inline]
+    fn default() -> Self {
+        VecDeque::new()
+    }
+}
+
+impl<T: Clone> Clone for VecDeque<T> {
+    fn clone(&self) -> Self {
+        let mut deque = VecDeque::with_capacity(self.len);
+        deque.extend(self.iter().cloned());
+        deque
+    }
+}
+
+impl<T: Debug> Debug for VecDeque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for VecDeque<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for VecDeque<T> {}
+
+impl<T> Extend<T> for VecDeque<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iterator = iter.into_iter();
+        let (lower, _) = iterator.size_hint();
+        self.reserve(lower);
+        for element in iterator {
+            self.push_back(element);
+        }
+    }
+}
+
+impl<T> FromIterator<T> for VecDeque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut deque = VecDeque::new();
+        deque.extend(iter);
+        deque
+    }
+}
+
+impl<T> From<Vec<T>> for VecDeque<T> {
+    /// Takes over the allocation of the `Vec` without copying.
+    fn from(vec: Vec<T>) -> Self {
+        let (ptr, len, cap) = vec.into_raw_parts();
+        Self {
+            head: 0,
+            len,
+            // SAFETY: both allocate with Layout::array::<T>(cap) through Global
+            buf: unsafe { RawVec::from_raw_parts(ptr, cap) },
+        }
+    }
+}
+
+impl<T> From<VecDeque<T>> for Vec<T> {
+    /// Moves the elements to the front of the buffer and reuses it.
+    fn from(mut deque: VecDeque<T>) -> Self {
+        deque.make_contiguous();
+        let deque = ManuallyDrop::new(deque);
+        unsafe {
+            if deque.head != 0 {
+                deque.copy(deque.head, 0, deque.len);
+            }
+            Vec::from_raw_parts(deque.ptr(), deque.len, deque.capacity())
+        }
+    }
+}
+
+impl<T> Drop for VecDeque<T> {
+    fn drop(&mut self) {
+        // the buffer is freed by RawVec
+        self.truncate(0);
+    }
+}
+
+impl<T> IntoIterator for VecDeque<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter { inner: self }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a VecDeque<T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut VecDeque<T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Iterates over the front slice, then the wrapped-around back slice.
+pub struct Iter<'a, T> {
+    i1: slice::Iter<'a, T>,
+    i2: slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        match self.i1.next() {
+            Some(element) => Some(element),
+            None => {
+                // the front is done, so continue with the back from now on
+                mem::swap(&mut self.i1, &mut self.i2);
+                self.i1.next()
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.i1.len() + self.i2.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        match self.i2.next_back() {
+            Some(element) => Some(element),
+            None => {
+                mem::swap(&mut self.i1, &mut self.i2);
+                self.i2.next_back()
+            }
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Iter {
+            i1: self.i1.clone(),
+            i2: self.i2.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Iter")
+            .field(&self.i1.as_slice())
+            .field(&self.i2.as_slice())
+            .finish()
+    }
+}
+
+pub struct IterMut<'a, T> {
+    i1: slice::IterMut<'a, T>,
+    i2: slice::IterMut<'a, T>,
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        match self.i1.next() {
+            Some(element) => Some(element),
+            None => {
+                mem::swap(&mut self.i1, &mut self.i2);
+                self.i1.next()
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.i1.len() + self.i2.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        match self.i2.next_back() {
+            Some(element) => Some(element),
+            None => {
+                mem::swap(&mut self.i1, &mut self.i2);
+                self.i2.next_back()
+            }
+        }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+impl<T: Debug> Debug for IterMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IterMut")
+            .field(&self.i1.as_slice())
+            .field(&self.i2.as_slice())
+            .finish()
+    }
+}
+
+/// Owns the deque and pops elements off either end.
+pub struct IntoIter<T> {
+    inner: VecDeque<T>,
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.inner.pop_front()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.inner.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.inner.pop_back()
+    }
+}
+
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for IntoIter<T> {}
+
+impl<T: Clone> Clone for IntoIter<T> {
+    fn clone(&self) -> Self {
+        IntoIter {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for IntoIter<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.inner).finish()
+    }
+}
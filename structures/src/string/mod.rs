@@ -1,33 +1,444 @@
-// use std::str::Utf8Error;
-
-// use super::vector::Vec;
-
-// #[derive(PartialEq, PartialOrd, Eq, Ord)]
-// pub struct String {
-//     vec: Vec<u8>,
-// }
-
-// pub struct FromUtf8Error {
-//     bytes: Vec<u8>,
-//     error: Utf8Error,
-// }
-
-// impl String {
-//     pub fn new() -> Self {
-//         String { vec: Vec::new() }
-//     }
-
-//     pub fn with_capacity(capacity: usize) -> Self {
-//         String {
-//             vec: Vec::with_capacity(capacity),
-//         }
-//     }
-
-//     pub unsafe fn from_raw_parts(buf: *mut u8, length: usize, capacity: usize) -> String {
-//         unsafe {
-//             String {
-//                 vec: Vec::from_raw_parts(buf, length, capacity),
-//             }
-//         }
-//     }
-// }
+#[macro_use]
+mod macros;
+pub mod rope;
+pub mod sharedstr;
+pub mod smallstring;
+
+use std::borrow::{Borrow, BorrowMut};
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::ops::{Add, AddAssign, Deref, DerefMut};
+use std::str::{self, FromStr, Utf8Error};
+use std::{char, ptr};
+
+pub use rope::Rope;
+pub use sharedstr::SharedStr;
+pub use smallstring::SmallString;
+
+use super::vector::Vec;
+
+/// A growable UTF-8 string backed by our `Vec<u8>`.
+#[derive(PartialEq, PartialOrd, Eq, Ord, Clone, Default)]
+pub struct String {
+    vec: Vec<u8>,
+}
+
+/// The error returned by [`String::from_utf8`], which hands back the rejected bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FromUtf8Error {
+    bytes: Vec<u8>,
+    error: Utf8Error,
+}
+
+impl String {
+    pub const fn new() -> Self {
+        String { vec: Vec::new() }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        String {
+            vec: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Converts a vector of bytes to a `String` if it is valid UTF-8, without copying.
+    pub fn from_utf8(vec: Vec<u8>) -> Result<String, FromUtf8Error> {
+        match str::from_utf8(&vec) {
+            Ok(_) => Ok(String { vec }),
+            Err(error) => Err(FromUtf8Error { bytes: vec, error }),
+        }
+    }
+
+    /// Converts bytes to a `String`, replacing invalid sequences with U+FFFD.
+    pub fn from_utf8_lossy(v: &[u8]) -> String {
+        let mut string = String::with_capacity(v.len());
+        for chunk in v.utf8_chunks() {
+            string.push_str(chunk.valid());
+            if !chunk.invalid().is_empty() {
+                string.push(char::REPLACEMENT_CHARACTER);
+            }
+        }
+        string
+    }
+
+    /// # Safety
+    ///
+    /// `bytes` must be valid UTF-8.
+    pub unsafe fn from_utf8_unchecked(bytes: Vec<u8>) -> String {
+        String { vec: bytes }
+    }
+
+    /// # Safety
+    ///
+    /// Same requirements as [`Vec::from_raw_parts`], and the first `length` bytes must be
+    /// valid UTF-8.
+    pub unsafe fn from_raw_parts(buf: *mut u8, length: usize, capacity: usize) -> String {
+        unsafe {
+            String {
+                vec: Vec::from_raw_parts(buf, length, capacity),
+            }
+        }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.vec
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the bytes are always valid UTF-8
+        unsafe { str::from_utf8_unchecked(&self.vec) }
+    }
+
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        // SAFETY: the bytes are always valid UTF-8
+        unsafe { str::from_utf8_unchecked_mut(&mut self.vec) }
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec
+    }
+
+    /// # Safety
+    ///
+    /// The bytes must still be valid UTF-8 once the borrow ends.
+    pub unsafe fn as_mut_vec(&mut self) -> &mut Vec<u8> {
+        &mut self.vec
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.vec.shrink_to_fit();
+    }
+
+    pub fn push_str(&mut self, string: &str) {
+        self.vec.extend_from_slice(string.as_bytes());
+    }
+
+    pub fn push(&mut self, ch: char) {
+        match ch.len_utf8() {
+            1 => self.vec.push(ch as u8),
+            _ => self
+                .vec
+                .extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes()),
+        }
+    }
+
+    /// Removes the last character, or returns `None` if the string is empty.
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.chars().next_back()?;
+        let new_len = self.len() - ch.len_utf8();
+        // SAFETY: new_len is the start of the last character, so it is a char boundary
+        unsafe { self.vec.set_len(new_len) };
+        Some(ch)
+    }
+
+    /// Shortens the string to `new_len` bytes, doing nothing if it is already shorter.
+    ///
+    /// Panics if `new_len` is not on a char boundary.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len <= self.len() {
+            assert!(
+                self.is_char_boundary(new_len),
+                "new_len is not on a char boundary"
+            );
+            self.vec.truncate(new_len);
+        }
+    }
+
+    /// Removes the character starting at byte `idx` and returns it.
+    ///
+    /// Panics if `idx` is not on a char boundary or is at the end of the string.
+    pub fn remove(&mut self, idx: usize) -> char {
+        let ch = match self[idx..].chars().next() {
+            Some(ch) => ch,
+            None => panic!("cannot remove a char from the end of a string"),
+        };
+
+        let next = idx + ch.len_utf8();
+        let len = self.len();
+        // SAFETY: idx..next is a whole character, so moving the rest down keeps the bytes
+        // valid UTF-8
+        unsafe {
+            ptr::copy(
+                self.vec.as_ptr().add(next),
+                self.vec.as_mut_ptr().add(idx),
+                len - next,
+            );
+            self.vec.set_len(len - (next - idx));
+        }
+        ch
+    }
+
+    /// Inserts a character at byte `idx`.
+    ///
+    /// Panics if `idx` is not on a char boundary.
+    pub fn insert(&mut self, idx: usize, ch: char) {
+        assert!(self.is_char_boundary(idx), "idx is not on a char boundary");
+        let mut bits = [0; 4];
+        let bits = ch.encode_utf8(&mut bits).as_bytes();
+        // SAFETY: idx is a char boundary and a whole character is inserted
+        unsafe { self.insert_bytes(idx, bits) };
+    }
+
+    /// Inserts a string slice at byte `idx`.
+    ///
+    /// Panics if `idx` is not on a char boundary.
+    pub fn insert_str(&mut self, idx: usize, string: &str) {
+        assert!(self.is_char_boundary(idx), "idx is not on a char boundary");
+        // SAFETY: idx is a char boundary and `string` is valid UTF-8
+        unsafe { self.insert_bytes(idx, string.as_bytes()) };
+    }
+
+    /// # Safety
+    ///
+    /// `idx` must be a char boundary and `bytes` valid UTF-8.
+    unsafe fn insert_bytes(&mut self, idx: usize, bytes: &[u8]) {
+        let len = self.len();
+        let amt = bytes.len();
+        self.vec.reserve(amt);
+
+        unsafe {
+            let ptr = self.vec.as_mut_ptr();
+            ptr::copy(ptr.add(idx), ptr.add(idx + amt), len - idx);
+            ptr::copy_nonoverlapping(bytes.as_ptr(), ptr.add(idx), amt);
+            self.vec.set_len(len + amt);
+        }
+    }
+
+    /// Splits the string in two at byte `at`, returning everything after it.
+    ///
+    /// Panics if `at` is not on a char boundary.
+    pub fn split_off(&mut self, at: usize) -> String {
+        assert!(self.is_char_boundary(at), "at is not on a char boundary");
+        String {
+            vec: self.vec.split_off(at),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.vec.clear();
+    }
+}
+
+impl FromUtf8Error {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the bytes that were passed to `from_utf8`.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+}
+
+impl Display for FromUtf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl Error for FromUtf8Error {}
+
+impl Deref for String {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl DerefMut for String {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl fmt::Write for String {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push(c);
+        Ok(())
+    }
+}
+
+impl Display for String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+impl Debug for String {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+/// Hashes like `str` does, so that `String` keys can be looked up with a `&str`.
+impl Hash for String {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl AsRef<str> for String {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl AsMut<str> for String {
+    fn as_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl AsRef<[u8]> for String {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Borrow<str> for String {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl BorrowMut<str> for String {
+    fn borrow_mut(&mut self) -> &mut str {
+        self
+    }
+}
+
+impl From<&str> for String {
+    fn from(s: &str) -> String {
+        String { vec: Vec::from(s) }
+    }
+}
+
+impl From<char> for String {
+    fn from(c: char) -> String {
+        let mut string = String::new();
+        string.push(c);
+        string
+    }
+}
+
+impl From<std::string::String> for String {
+    fn from(s: std::string::String) -> String {
+        String {
+            vec: Vec::from(s.into_bytes()),
+        }
+    }
+}
+
+impl From<String> for std::string::String {
+    fn from(s: String) -> std::string::String {
+        // SAFETY: the bytes are valid UTF-8
+        unsafe { std::string::String::from_utf8_unchecked(s.vec.into()) }
+    }
+}
+
+impl From<String> for Vec<u8> {
+    fn from(s: String) -> Vec<u8> {
+        s.into_bytes()
+    }
+}
+
+impl FromStr for String {
+    type Err = core::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<String, Self::Err> {
+        Ok(String::from(s))
+    }
+}
+
+impl Extend<char> for String {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        let iterator = iter.into_iter();
+        let (lower, _) = iterator.size_hint();
+        self.reserve(lower);
+        iterator.for_each(move |c| self.push(c));
+    }
+}
+
+impl<'a> Extend<&'a char> for String {
+    fn extend<I: IntoIterator<Item = &'a char>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<'a> Extend<&'a str> for String {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |s| self.push_str(s));
+    }
+}
+
+impl FromIterator<char> for String {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> String {
+        let mut string = String::new();
+        string.extend(iter);
+        string
+    }
+}
+
+impl<'a> FromIterator<&'a str> for String {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> String {
+        let mut string = String::new();
+        string.extend(iter);
+        string
+    }
+}
+
+impl Add<&str> for String {
+    type Output = String;
+
+    fn add(mut self, other: &str) -> String {
+        self.push_str(other);
+        self
+    }
+}
+
+impl AddAssign<&str> for String {
+    fn add_assign(&mut self, other: &str) {
+        self.push_str(other);
+    }
+}
+
+impl_str_eq! { String, str }
+impl_str_eq! { String, &'a str }
+impl_str_eq! { String, std::string::String }
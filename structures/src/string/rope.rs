@@ -0,0 +1,654 @@
+use std::fmt::{self, Debug, Display};
+use std::ops::RangeBounds;
+use std::slice;
+
+use super::String;
+use crate::vector::Vec;
+
+/// Leaves hold at most this many bytes, so edits only copy a small chunk of text.
+const MAX_LEAF: usize = 512;
+
+/// Totals for a subtree, used to find offsets without looking at the text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct TextInfo {
+    bytes: usize,
+    chars: usize,
+    newlines: usize,
+}
+
+impl TextInfo {
+    fn of(text: &str) -> Self {
+        TextInfo {
+            bytes: text.len(),
+            chars: text.chars().count(),
+            newlines: text.bytes().filter(|&b| b == b'\n').count(),
+        }
+    }
+
+    fn add(self, other: Self) -> Self {
+        TextInfo {
+            bytes: self.bytes + other.bytes,
+            chars: self.chars + other.chars,
+            newlines: self.newlines + other.newlines,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Node {
+    Leaf {
+        info: TextInfo,
+        text: String,
+    },
+    Branch {
+        info: TextInfo,
+        height: u8,
+        left: Box<Node>,
+        right: Box<Node>,
+    },
+}
+
+impl Node {
+    fn leaf(text: String) -> Node {
+        Node::Leaf {
+            info: TextInfo::of(&text),
+            text,
+        }
+    }
+
+    fn branch(left: Node, right: Node) -> Node {
+        Node::Branch {
+            info: left.info().add(right.info()),
+            height: left.height().max(right.height()) + 1,
+            left: Box::new(left),
+            right: Box::new(right),
+        }
+    }
+
+    /// Builds a balanced tree out of `text`, cut into leaves of about the same size at char
+    /// boundaries, so that the leaves have room left for later inserts.
+    fn from_str(text: &str) -> Node {
+        if text.len() <= MAX_LEAF {
+            return Node::leaf(String::from(text));
+        }
+
+        let leaves = text.len().div_ceil(MAX_LEAF / 2 + 1);
+        let target = text.len().div_ceil(leaves);
+        let mut chunks: Vec<&str> = Vec::with_capacity(leaves + 1);
+        let mut rest = text;
+        while !rest.is_empty() {
+            let mut at = rest.len().min(target);
+            while !rest.is_char_boundary(at) {
+                at -= 1;
+            }
+            let (chunk, tail) = rest.split_at(at);
+            chunks.push(chunk);
+            rest = tail;
+        }
+        Node::build(&chunks)
+    }
+
+    fn build(chunks: &[&str]) -> Node {
+        match chunks {
+            [chunk] => Node::leaf(String::from(*chunk)),
+            _ => {
+                let (left, right) = chunks.split_at(chunks.len() / 2);
+                Node::branch(Node::build(left), Node::build(right))
+            }
+        }
+    }
+
+    #[inline]
+    fn info(&self) -> TextInfo {
+        match self {
+            Node::Leaf { info, .. } | Node::Branch { info, .. } => *info,
+        }
+    }
+
+    #[inline]
+    fn height(&self) -> u8 {
+        match self {
+            Node::Leaf { .. } => 0,
+            Node::Branch { height, .. } => *height,
+        }
+    }
+
+    fn into_children(self) -> (Node, Node) {
+        match self {
+            Node::Branch { left, right, .. } => (*left, *right),
+            Node::Leaf { .. } => unreachable!("leaves have no children"),
+        }
+    }
+
+    /// `(a, (b, c))` into `((a, b), c)`.
+    fn rotate_left(self) -> Node {
+        let (a, right) = self.into_children();
+        let (b, c) = right.into_children();
+        Node::branch(Node::branch(a, b), c)
+    }
+
+    /// `((a, b), c)` into `(a, (b, c))`.
+    fn rotate_right(self) -> Node {
+        let (left, c) = self.into_children();
+        let (a, b) = left.into_children();
+        Node::branch(a, Node::branch(b, c))
+    }
+
+    /// Concatenates two balanced trees into one, in time proportional to the difference in
+    /// their heights.
+    fn join(left: Node, right: Node) -> Node {
+        if left.info().bytes == 0 {
+            return right;
+        }
+        if right.info().bytes == 0 {
+            return left;
+        }
+
+        let (hl, hr) = (left.height(), right.height());
+        if hl > hr + 1 {
+            Node::join_right(left, right)
+        } else if hr > hl + 1 {
+            Node::join_left(left, right)
+        } else {
+            Node::join_balanced(left, right)
+        }
+    }
+
+    /// Joins trees of about the same height, merging two small leaves into one.
+    fn join_balanced(left: Node, right: Node) -> Node {
+        match (left, right) {
+            (Node::Leaf { text: mut l, .. }, Node::Leaf { text: r, .. })
+                if l.len() + r.len() <= MAX_LEAF =>
+            {
+                l.push_str(&r);
+                Node::leaf(l)
+            }
+            (left, right) => Node::branch(left, right),
+        }
+    }
+
+    /// Joins `right` into the right spine of the taller `left`.
+    fn join_right(left: Node, right: Node) -> Node {
+        let (l, c) = left.into_children();
+        if c.height() <= right.height() + 1 {
+            let joined = Node::join_balanced(c, right);
+            if joined.height() <= l.height() + 1 {
+                Node::branch(l, joined)
+            } else {
+                Node::branch(l, joined.rotate_right()).rotate_left()
+            }
+        } else {
+            let joined = Node::join_right(c, right);
+            let rebalance = joined.height() > l.height() + 1;
+            let node = Node::branch(l, joined);
+            if rebalance {
+                node.rotate_left()
+            } else {
+                node
+            }
+        }
+    }
+
+    /// Joins `left` into the left spine of the taller `right`.
+    fn join_left(left: Node, right: Node) -> Node {
+        let (c, r) = right.into_children();
+        if c.height() <= left.height() + 1 {
+            let joined = Node::join_balanced(left, c);
+            if joined.height() <= r.height() + 1 {
+                Node::branch(joined, r)
+            } else {
+                Node::branch(joined.rotate_left(), r).rotate_right()
+            }
+        } else {
+            let joined = Node::join_left(left, c);
+            let rebalance = joined.height() > r.height() + 1;
+            let node = Node::branch(joined, r);
+            if rebalance {
+                node.rotate_right()
+            } else {
+                node
+            }
+        }
+    }
+
+    /// Splits the tree at byte `idx`, which must be a char boundary.
+    fn split(self, idx: usize) -> (Node, Node) {
+        match self {
+            Node::Leaf { mut text, .. } => {
+                let right = text.split_off(idx);
+                (Node::leaf(text), Node::leaf(right))
+            }
+            Node::Branch { left, right, .. } => {
+                let left_bytes = left.info().bytes;
+                if idx < left_bytes {
+                    let (a, b) = left.split(idx);
+                    (a, Node::join(b, *right))
+                } else if idx > left_bytes {
+                    let (a, b) = right.split(idx - left_bytes);
+                    (Node::join(*left, a), b)
+                } else {
+                    (*left, *right)
+                }
+            }
+        }
+    }
+
+    /// Inserts `text` at byte `idx`, editing the leaf in place when the text fits.
+    fn insert(self, idx: usize, text: &str) -> Node {
+        match self {
+            Node::Leaf {
+                mut info,
+                text: mut leaf,
+            } => {
+                if leaf.len() + text.len() <= MAX_LEAF {
+                    leaf.insert_str(idx, text);
+                    info = info.add(TextInfo::of(text));
+                    Node::Leaf { info, text: leaf }
+                } else {
+                    let tail = leaf.split_off(idx);
+                    leaf.push_str(text);
+                    leaf.push_str(&tail);
+                    Node::from_str(&leaf)
+                }
+            }
+            Node::Branch { left, right, .. } => {
+                let left_bytes = left.info().bytes;
+                if idx <= left_bytes {
+                    Node::join(left.insert(idx, text), *right)
+                } else {
+                    Node::join(*left, right.insert(idx - left_bytes, text))
+                }
+            }
+        }
+    }
+
+    /// Walks down to the leaf containing byte `idx`, returning it with `idx` made relative to
+    /// it and the totals of everything before it.
+    fn leaf_at_byte(&self, mut idx: usize) -> (&str, usize, TextInfo) {
+        let mut node = self;
+        let mut before = TextInfo::default();
+        loop {
+            match node {
+                Node::Leaf { text, .. } => return (text, idx, before),
+                Node::Branch { left, right, .. } => {
+                    let left_info = left.info();
+                    if idx < left_info.bytes {
+                        node = left;
+                    } else {
+                        idx -= left_info.bytes;
+                        before = before.add(left_info);
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Same as `leaf_at_byte`, but for the leaf containing char `idx`.
+    fn leaf_at_char(&self, mut idx: usize) -> (&str, usize, TextInfo) {
+        let mut node = self;
+        let mut before = TextInfo::default();
+        loop {
+            match node {
+                Node::Leaf { text, .. } => return (text, idx, before),
+                Node::Branch { left, right, .. } => {
+                    let left_info = left.info();
+                    if idx < left_info.chars {
+                        node = left;
+                    } else {
+                        idx -= left_info.chars;
+                        before = before.add(left_info);
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Same as `leaf_at_byte`, but for the leaf containing the `n`th newline, counting from 1.
+    fn leaf_at_newline(&self, mut n: usize) -> (&str, usize, TextInfo) {
+        let mut node = self;
+        let mut before = TextInfo::default();
+        loop {
+            match node {
+                Node::Leaf { text, .. } => return (text, n, before),
+                Node::Branch { left, right, .. } => {
+                    let left_info = left.info();
+                    if n <= left_info.newlines {
+                        node = left;
+                    } else {
+                        n -= left_info.newlines;
+                        before = before.add(left_info);
+                        node = right;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A string stored as a balanced tree of small chunks, so that edits anywhere in a large
+/// text take O(log n) instead of copying everything after the edit.
+///
+/// Lines are separated by `\n`, so a text with `n` newlines has `n + 1` lines.
+#[derive(Clone)]
+pub struct Rope {
+    root: Node,
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Rope {
+            root: Node::leaf(String::new()),
+        }
+    }
+
+    #[inline]
+    pub fn len_bytes(&self) -> usize {
+        self.root.info().bytes
+    }
+
+    #[inline]
+    pub fn len_chars(&self) -> usize {
+        self.root.info().chars
+    }
+
+    #[inline]
+    pub fn len_lines(&self) -> usize {
+        self.root.info().newlines + 1
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len_bytes() == 0
+    }
+
+    pub fn is_char_boundary(&self, byte_idx: usize) -> bool {
+        if byte_idx >= self.len_bytes() {
+            return byte_idx == self.len_bytes();
+        }
+        let (leaf, idx, _) = self.root.leaf_at_byte(byte_idx);
+        leaf.is_char_boundary(idx)
+    }
+
+    fn assert_char_boundary(&self, byte_idx: usize) {
+        assert!(
+            self.is_char_boundary(byte_idx),
+            "byte index {byte_idx} is not a char boundary of a rope of {} bytes",
+            self.len_bytes()
+        );
+    }
+
+    /// Inserts `text` at byte `byte_idx`.
+    ///
+    /// Panics if `byte_idx` is not on a char boundary.
+    pub fn insert(&mut self, byte_idx: usize, text: &str) {
+        self.assert_char_boundary(byte_idx);
+        if text.is_empty() {
+            return;
+        }
+        let root = std::mem::replace(&mut self.root, Node::leaf(String::new()));
+        self.root = root.insert(byte_idx, text);
+    }
+
+    /// Inserts `text` before the char at `char_idx`.
+    pub fn insert_at_char(&mut self, char_idx: usize, text: &str) {
+        let byte_idx = self.char_to_byte(char_idx);
+        self.insert(byte_idx, text);
+    }
+
+    /// Removes the bytes in `range`.
+    ///
+    /// Panics if either end is out of bounds or not on a char boundary.
+    pub fn remove<R: RangeBounds<usize>>(&mut self, range: R) {
+        let range = slice::range(range, ..self.len_bytes());
+        self.assert_char_boundary(range.start);
+        self.assert_char_boundary(range.end);
+        if range.is_empty() {
+            return;
+        }
+
+        let root = std::mem::replace(&mut self.root, Node::leaf(String::new()));
+        let (head, rest) = root.split(range.start);
+        let (_, tail) = rest.split(range.end - range.start);
+        self.root = Node::join(head, tail);
+    }
+
+    /// Removes the chars in `range`.
+    pub fn remove_chars<R: RangeBounds<usize>>(&mut self, range: R) {
+        let range = slice::range(range, ..self.len_chars());
+        let start = self.char_to_byte(range.start);
+        let end = self.char_to_byte(range.end);
+        self.remove(start..end);
+    }
+
+    /// Moves all text of `other` to the end of `self`.
+    pub fn append(&mut self, other: Rope) {
+        let root = std::mem::replace(&mut self.root, Node::leaf(String::new()));
+        self.root = Node::join(root, other.root);
+    }
+
+    /// Splits the rope at byte `byte_idx`, returning everything after it.
+    pub fn split_off(&mut self, byte_idx: usize) -> Rope {
+        assert!(byte_idx <= self.len_bytes(), "byte index out of bounds");
+        self.assert_char_boundary(byte_idx);
+        let root = std::mem::replace(&mut self.root, Node::leaf(String::new()));
+        let (left, right) = root.split(byte_idx);
+        self.root = left;
+        Rope { root: right }
+    }
+
+    /// Returns the byte offset of the char at `char_idx`, or the length for one past the end.
+    pub fn char_to_byte(&self, char_idx: usize) -> usize {
+        assert!(char_idx <= self.len_chars(), "char index out of bounds");
+        if char_idx == self.len_chars() {
+            return self.len_bytes();
+        }
+        let (leaf, idx, before) = self.root.leaf_at_char(char_idx);
+        let offset = leaf.char_indices().nth(idx).map_or(leaf.len(), |(i, _)| i);
+        before.bytes + offset
+    }
+
+    /// Returns the index of the char containing byte `byte_idx`.
+    pub fn byte_to_char(&self, byte_idx: usize) -> usize {
+        assert!(byte_idx <= self.len_bytes(), "byte index out of bounds");
+        if byte_idx == self.len_bytes() {
+            return self.len_chars();
+        }
+        let (leaf, idx, before) = self.root.leaf_at_byte(byte_idx);
+        let chars = leaf.bytes().take(idx + 1).filter(|&b| !is_continuation(b));
+        before.chars + chars.count() - 1
+    }
+
+    /// Returns the index of the line containing byte `byte_idx`.
+    pub fn byte_to_line(&self, byte_idx: usize) -> usize {
+        assert!(byte_idx <= self.len_bytes(), "byte index out of bounds");
+        if byte_idx == self.len_bytes() {
+            return self.len_lines() - 1;
+        }
+        let (leaf, idx, before) = self.root.leaf_at_byte(byte_idx);
+        before.newlines
+            + leaf.as_bytes()[..idx]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count()
+    }
+
+    /// Returns the byte offset where line `line_idx` starts.
+    pub fn line_to_byte(&self, line_idx: usize) -> usize {
+        assert!(line_idx < self.len_lines(), "line index out of bounds");
+        if line_idx == 0 {
+            return 0;
+        }
+        let (leaf, n, before) = self.root.leaf_at_newline(line_idx);
+        let (newline, _) = leaf
+            .match_indices('\n')
+            .nth(n - 1)
+            .expect("leaf holds the newline");
+        before.bytes + newline + 1
+    }
+
+    /// Returns the chunks of line `line_idx`, including its trailing newline if it has one.
+    pub fn line(&self, line_idx: usize) -> Chunks<'_> {
+        let start = self.line_to_byte(line_idx);
+        let end = if line_idx + 1 < self.len_lines() {
+            self.line_to_byte(line_idx + 1)
+        } else {
+            self.len_bytes()
+        };
+        self.chunks_in(start..end)
+    }
+
+    /// Returns the text as a sequence of string slices, in order.
+    pub fn chunks(&self) -> Chunks<'_> {
+        self.chunks_in(..)
+    }
+
+    /// Returns the text of the byte `range` as a sequence of string slices.
+    ///
+    /// Panics if either end is out of bounds or not on a char boundary.
+    pub fn chunks_in<R: RangeBounds<usize>>(&self, range: R) -> Chunks<'_> {
+        let range = slice::range(range, ..self.len_bytes());
+        self.assert_char_boundary(range.start);
+        self.assert_char_boundary(range.end);
+
+        let mut stack = Vec::new();
+        stack.push((&self.root, 0));
+        Chunks {
+            stack,
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+
+#[inline]
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+/// Iterator over the chunks of a `Rope`, see [`Rope::chunks`].
+pub struct Chunks<'a> {
+    // subtrees left to visit with their starting byte offsets, the next one on top
+    stack: Vec<(&'a Node, usize)>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for Chunks<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.start >= self.end {
+            return None;
+        }
+        while let Some((node, offset)) = self.stack.pop() {
+            let bytes = node.info().bytes;
+            if bytes == 0 || offset + bytes <= self.start || offset >= self.end {
+                continue;
+            }
+            match node {
+                Node::Leaf { text, .. } => {
+                    let from = self.start.max(offset) - offset;
+                    let to = self.end.min(offset + bytes) - offset;
+                    return Some(&text[from..to]);
+                }
+                Node::Branch { left, right, .. } => {
+                    self.stack.push((right, offset + left.info().bytes));
+                    self.stack.push((left, offset));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Debug for Chunks<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Chunks")
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .finish()
+    }
+}
+
+impl Default for Rope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        Rope {
+            root: Node::from_str(text),
+        }
+    }
+}
+
+impl From<String> for Rope {
+    fn from(text: String) -> Self {
+        if text.len() <= MAX_LEAF {
+            return Rope {
+                root: Node::leaf(text),
+            };
+        }
+        Rope::from(text.as_str())
+    }
+}
+
+impl From<&Rope> for String {
+    fn from(rope: &Rope) -> String {
+        let mut text = String::with_capacity(rope.len_bytes());
+        rope.chunks().for_each(|chunk| text.push_str(chunk));
+        text
+    }
+}
+
+impl From<Rope> for String {
+    fn from(rope: Rope) -> String {
+        String::from(&rope)
+    }
+}
+
+impl Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.chunks().try_for_each(|chunk| f.write_str(chunk))
+    }
+}
+
+impl Debug for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.chunks()).finish()
+    }
+}
+
+impl PartialEq for Rope {
+    fn eq(&self, other: &Rope) -> bool {
+        self.len_bytes() == other.len_bytes()
+            && self
+                .chunks()
+                .flat_map(str::bytes)
+                .eq(other.chunks().flat_map(str::bytes))
+    }
+}
+
+impl Eq for Rope {}
+
+impl PartialEq<str> for Rope {
+    fn eq(&self, other: &str) -> bool {
+        self.len_bytes() == other.len() && self.chunks().flat_map(str::bytes).eq(other.bytes())
+    }
+}
+
+impl PartialEq<&str> for Rope {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq<String> for Rope {
+    fn eq(&self, other: &String) -> bool {
+        *self == **other
+    }
+}
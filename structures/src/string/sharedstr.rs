@@ -0,0 +1,206 @@
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::process;
+use std::ptr::NonNull;
+use std::str;
+use std::sync::atomic::{self, AtomicUsize, Ordering};
+
+use super::{SmallString, String};
+use crate::vector::Vec;
+
+/// Refcounts above this abort, so that leaked clones can never overflow the count.
+const MAX_REFCOUNT: usize = isize::MAX as usize;
+
+struct Inner {
+    count: AtomicUsize,
+    bytes: Vec<u8>,
+}
+
+/// An immutable UTF-8 string whose clones share one allocation, counted atomically so that
+/// clones can be sent between threads.
+pub struct SharedStr {
+    ptr: NonNull<Inner>,
+    phantom: PhantomData<Inner>,
+}
+
+// SAFETY: the bytes are never mutated while shared, and the count is atomic
+unsafe impl Send for SharedStr {}
+unsafe impl Sync for SharedStr {}
+
+impl SharedStr {
+    fn from_bytes(bytes: Vec<u8>) -> Self {
+        let inner = Box::new(Inner {
+            count: AtomicUsize::new(1),
+            bytes,
+        });
+        SharedStr {
+            ptr: NonNull::from(Box::leak(inner)),
+            phantom: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn inner(&self) -> &Inner {
+        // SAFETY: the allocation lives as long as any clone does
+        unsafe { self.ptr.as_ref() }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the bytes came from a `str` or a `String` and are never mutated
+        unsafe { str::from_utf8_unchecked(&self.inner().bytes) }
+    }
+
+    /// Number of clones sharing this string, including `this`.
+    pub fn strong_count(this: &Self) -> usize {
+        this.inner().count.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` if both share the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.ptr == other.ptr
+    }
+
+    /// Converts to an owned `String`, reusing the buffer if this is the only clone.
+    pub fn into_string(self) -> String {
+        if self.inner().count.load(Ordering::Acquire) == 1 {
+            let this = std::mem::ManuallyDrop::new(self);
+            // SAFETY: this was the only clone, so nobody else can reach the allocation
+            let inner = unsafe { Box::from_raw(this.ptr.as_ptr()) };
+            // SAFETY: the bytes are valid UTF-8
+            unsafe { String::from_utf8_unchecked(inner.bytes) }
+        } else {
+            String::from(self.as_str())
+        }
+    }
+}
+
+impl Clone for SharedStr {
+    fn clone(&self) -> Self {
+        // a new clone can only be made from an existing one, which already synchronizes the
+        // allocation, so Relaxed is enough
+        let old = self.inner().count.fetch_add(1, Ordering::Relaxed);
+        if old > MAX_REFCOUNT {
+            process::abort();
+        }
+        SharedStr {
+            ptr: self.ptr,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl Drop for SharedStr {
+    fn drop(&mut self) {
+        if self.inner().count.fetch_sub(1, Ordering::Release) != 1 {
+            return;
+        }
+        // make every other clone's last use happen before the allocation is freed
+        atomic::fence(Ordering::Acquire);
+        // SAFETY: this was the last clone
+        drop(unsafe { Box::from_raw(self.ptr.as_ptr()) });
+    }
+}
+
+impl Default for SharedStr {
+    fn default() -> Self {
+        SharedStr::from_bytes(Vec::new())
+    }
+}
+
+impl Deref for SharedStr {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for SharedStr {
+    fn from(s: &str) -> Self {
+        SharedStr::from_bytes(Vec::from(s))
+    }
+}
+
+/// Takes over the buffer without copying.
+impl From<String> for SharedStr {
+    fn from(s: String) -> Self {
+        SharedStr::from_bytes(s.into_bytes())
+    }
+}
+
+impl From<SmallString> for SharedStr {
+    fn from(s: SmallString) -> Self {
+        SharedStr::from(s.into_string())
+    }
+}
+
+impl From<SharedStr> for String {
+    fn from(s: SharedStr) -> String {
+        s.into_string()
+    }
+}
+
+impl AsRef<str> for SharedStr {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl AsRef<[u8]> for SharedStr {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Borrow<str> for SharedStr {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl Display for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+impl Debug for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Hash for SharedStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl PartialEq for SharedStr {
+    fn eq(&self, other: &Self) -> bool {
+        SharedStr::ptr_eq(self, other) || self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SharedStr {}
+
+impl PartialOrd for SharedStr {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SharedStr {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl_str_eq! { SharedStr, str }
+impl_str_eq! { SharedStr, &'a str }
+impl_str_eq! { SharedStr, String }
@@ -0,0 +1,505 @@
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::mem::{self, ManuallyDrop};
+use std::ops::{Deref, DerefMut};
+use std::ptr::{self, NonNull};
+use std::str::{self, FromStr};
+
+use super::String;
+use crate::vector::Vec;
+
+/// The heap representation: the parts of a `Vec<u8>`, with the capacity stored as
+/// little-endian bytes so that its last byte can double as the tag.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct HeapRepr {
+    ptr: NonNull<u8>,
+    len: usize,
+    cap: [u8; mem::size_of::<usize>()],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct InlineRepr {
+    buf: [u8; INLINE_CAP],
+    len: u8,
+}
+
+/// Both representations end in the same byte: the inline length, or `HEAP_TAG` once the
+/// string has spilled to the heap.
+#[repr(C)]
+union Repr {
+    inline: InlineRepr,
+    heap: HeapRepr,
+}
+
+/// Bytes that fit inline, 23 on 64-bit targets.
+pub const INLINE_CAP: usize = mem::size_of::<HeapRepr>() - 1;
+
+const HEAP_TAG: u8 = u8::MAX;
+
+/// The largest heap capacity whose last little-endian byte is free for the tag.
+const MAX_HEAP_CAP: usize = usize::MAX >> 8;
+
+/// A UTF-8 string that stores up to [`INLINE_CAP`] bytes inline, in the same space a
+/// `Vec<u8>` takes, and spills to a `Vec<u8>` beyond that.
+pub struct SmallString {
+    repr: Repr,
+}
+
+// SAFETY: the heap buffer is uniquely owned, just like a `Vec<u8>`
+unsafe impl Send for SmallString {}
+unsafe impl Sync for SmallString {}
+
+impl SmallString {
+    pub const fn new() -> Self {
+        SmallString {
+            repr: Repr {
+                inline: InlineRepr {
+                    buf: [0; INLINE_CAP],
+                    len: 0,
+                },
+            },
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut s = SmallString::new();
+        s.reserve(capacity);
+        s
+    }
+
+    /// Returns `true` if the bytes have been moved into a heap allocation.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        // SAFETY: the last byte is initialized in both representations
+        unsafe { self.repr.inline.len == HEAP_TAG }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        // SAFETY: the tag tells which representation is active
+        unsafe {
+            if self.spilled() {
+                self.repr.heap.len
+            } else {
+                self.repr.inline.len as usize
+            }
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        if self.spilled() {
+            // SAFETY: the string has spilled
+            decode_cap(unsafe { self.repr.heap.cap })
+        } else {
+            INLINE_CAP
+        }
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *const u8 {
+        // SAFETY: the tag tells which representation is active
+        unsafe {
+            if self.spilled() {
+                self.repr.heap.ptr.as_ptr()
+            } else {
+                self.repr.inline.buf.as_ptr()
+            }
+        }
+    }
+
+    #[inline]
+    fn as_mut_ptr(&mut self) -> *mut u8 {
+        // SAFETY: the tag tells which representation is active
+        unsafe {
+            if self.spilled() {
+                self.repr.heap.ptr.as_ptr()
+            } else {
+                self.repr.inline.buf.as_mut_ptr()
+            }
+        }
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        // SAFETY: the first len bytes are initialized
+        unsafe { std::slice::from_raw_parts(self.as_ptr(), self.len()) }
+    }
+
+    #[inline]
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the bytes are always valid UTF-8
+        unsafe { str::from_utf8_unchecked(self.as_bytes()) }
+    }
+
+    #[inline]
+    pub fn as_mut_str(&mut self) -> &mut str {
+        let len = self.len();
+        // SAFETY: the first len bytes are initialized and valid UTF-8
+        unsafe {
+            str::from_utf8_unchecked_mut(std::slice::from_raw_parts_mut(self.as_mut_ptr(), len))
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `len` must not exceed the capacity, and the first `len` bytes must be initialized and
+    /// valid UTF-8.
+    #[inline]
+    unsafe fn set_len(&mut self, len: usize) {
+        if self.spilled() {
+            self.repr.heap.len = len;
+        } else {
+            self.repr.inline.len = len as u8;
+        }
+    }
+
+    /// Runs `f` on the heap buffer as a `Vec<u8>` and stores its parts back afterwards, even
+    /// if `f` panics.
+    fn with_vec<R>(&mut self, f: impl FnOnce(&mut Vec<u8>) -> R) -> R {
+        debug_assert!(self.spilled());
+
+        struct WriteBack<'a> {
+            heap: &'a mut HeapRepr,
+            vec: ManuallyDrop<Vec<u8>>,
+        }
+
+        impl Drop for WriteBack<'_> {
+            fn drop(&mut self) {
+                if self.vec.capacity() > MAX_HEAP_CAP {
+                    self.vec.shrink_to(MAX_HEAP_CAP);
+                }
+                // SAFETY: a Vec's pointer is never null
+                self.heap.ptr = unsafe { NonNull::new_unchecked(self.vec.as_mut_ptr()) };
+                self.heap.len = self.vec.len();
+                self.heap.cap = encode_cap(self.vec.capacity());
+            }
+        }
+
+        // SAFETY: the string has spilled, so the heap parts came from a `Vec<u8>`, which is
+        // handed back before anyone else can look at them
+        let mut guard = unsafe {
+            let heap = &mut self.repr.heap;
+            let vec = Vec::from_raw_parts(heap.ptr.as_ptr(), heap.len, decode_cap(heap.cap));
+            WriteBack {
+                heap,
+                vec: ManuallyDrop::new(vec),
+            }
+        };
+        f(&mut guard.vec)
+    }
+
+    /// Moves the inline bytes to a heap buffer with room for at least `capacity` bytes.
+    fn spill(&mut self, capacity: usize) {
+        debug_assert!(!self.spilled());
+        let mut vec = Vec::with_capacity(capacity);
+        vec.extend_from_slice(self.as_bytes());
+        self.repr.heap = into_heap(vec);
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self
+            .len()
+            .checked_add(additional)
+            .filter(|&needed| needed <= MAX_HEAP_CAP)
+            .expect("capacity overflow");
+        if self.spilled() {
+            self.with_vec(|vec| vec.reserve(additional));
+        } else if needed > INLINE_CAP {
+            self.spill(needed);
+        }
+    }
+
+    /// Moves the bytes back inline if they fit, or shrinks the heap buffer otherwise.
+    pub fn shrink_to_fit(&mut self) {
+        if !self.spilled() {
+            return;
+        }
+        if self.len() <= INLINE_CAP {
+            let vec = self.take_vec();
+            let mut inline = InlineRepr {
+                buf: [0; INLINE_CAP],
+                len: vec.len() as u8,
+            };
+            inline.buf[..vec.len()].copy_from_slice(&vec);
+            self.repr.inline = inline;
+        } else {
+            self.with_vec(|vec| vec.shrink_to_fit());
+        }
+    }
+
+    /// Takes the heap buffer out, leaving an empty inline string behind.
+    fn take_vec(&mut self) -> Vec<u8> {
+        debug_assert!(self.spilled());
+        // SAFETY: the heap parts came from a `Vec<u8>`, and `self` no longer refers to them
+        unsafe {
+            let heap = self.repr.heap;
+            self.repr.inline = InlineRepr {
+                buf: [0; INLINE_CAP],
+                len: 0,
+            };
+            Vec::from_raw_parts(heap.ptr.as_ptr(), heap.len, decode_cap(heap.cap))
+        }
+    }
+
+    pub fn push_str(&mut self, string: &str) {
+        let len = self.len();
+        self.reserve(string.len());
+        if self.spilled() {
+            self.with_vec(|vec| vec.extend_from_slice(string.as_bytes()));
+        } else {
+            // SAFETY: reserve left room inline, and a whole string slice is appended
+            unsafe {
+                ptr::copy_nonoverlapping(string.as_ptr(), self.as_mut_ptr().add(len), string.len());
+                self.set_len(len + string.len());
+            }
+        }
+    }
+
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Removes the last character, or returns `None` if the string is empty.
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.chars().next_back()?;
+        let new_len = self.len() - ch.len_utf8();
+        // SAFETY: new_len is the start of the last character
+        unsafe { self.set_len(new_len) };
+        Some(ch)
+    }
+
+    /// Shortens the string to `new_len` bytes, doing nothing if it is already shorter.
+    ///
+    /// Panics if `new_len` is not on a char boundary.
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len <= self.len() {
+            assert!(
+                self.is_char_boundary(new_len),
+                "new_len is not on a char boundary"
+            );
+            // SAFETY: new_len is a char boundary within the string
+            unsafe { self.set_len(new_len) };
+        }
+    }
+
+    pub fn clear(&mut self) {
+        // SAFETY: the empty string is valid
+        unsafe { self.set_len(0) };
+    }
+
+    pub fn into_string(mut self) -> String {
+        if self.spilled() {
+            // SAFETY: the bytes are valid UTF-8
+            unsafe { String::from_utf8_unchecked(self.take_vec()) }
+        } else {
+            String::from(self.as_str())
+        }
+    }
+}
+
+#[inline]
+fn encode_cap(cap: usize) -> [u8; mem::size_of::<usize>()] {
+    debug_assert!(cap <= MAX_HEAP_CAP);
+    let mut bytes = cap.to_le_bytes();
+    bytes[bytes.len() - 1] = HEAP_TAG;
+    bytes
+}
+
+#[inline]
+fn decode_cap(mut bytes: [u8; mem::size_of::<usize>()]) -> usize {
+    let last = bytes.len() - 1;
+    bytes[last] = 0;
+    usize::from_le_bytes(bytes)
+}
+
+fn into_heap(vec: Vec<u8>) -> HeapRepr {
+    let mut vec = ManuallyDrop::new(vec);
+    if vec.capacity() > MAX_HEAP_CAP {
+        vec.shrink_to(MAX_HEAP_CAP);
+    }
+    HeapRepr {
+        // SAFETY: a Vec's pointer is never null
+        ptr: unsafe { NonNull::new_unchecked(vec.as_mut_ptr()) },
+        len: vec.len(),
+        cap: encode_cap(vec.capacity()),
+    }
+}
+
+impl Drop for SmallString {
+    fn drop(&mut self) {
+        if self.spilled() {
+            drop(self.take_vec());
+        }
+    }
+}
+
+impl Clone for SmallString {
+    fn clone(&self) -> Self {
+        SmallString::from(self.as_str())
+    }
+}
+
+impl Default for SmallString {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for SmallString {
+    type Target = str;
+
+    #[inline]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl DerefMut for SmallString {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut str {
+        self.as_mut_str()
+    }
+}
+
+impl From<&str> for SmallString {
+    fn from(s: &str) -> Self {
+        let mut string = SmallString::new();
+        string.push_str(s);
+        string
+    }
+}
+
+/// Reuses the buffer if the string is too long to be stored inline.
+impl From<String> for SmallString {
+    fn from(s: String) -> Self {
+        if s.len() <= INLINE_CAP {
+            return SmallString::from(s.as_str());
+        }
+        SmallString {
+            repr: Repr {
+                heap: into_heap(s.into_bytes()),
+            },
+        }
+    }
+}
+
+impl From<SmallString> for String {
+    fn from(s: SmallString) -> String {
+        s.into_string()
+    }
+}
+
+impl FromStr for SmallString {
+    type Err = core::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(SmallString::from(s))
+    }
+}
+
+impl fmt::Write for SmallString {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+impl Extend<char> for SmallString {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        let iterator = iter.into_iter();
+        self.reserve(iterator.size_hint().0);
+        iterator.for_each(move |c| self.push(c));
+    }
+}
+
+impl<'a> Extend<&'a str> for SmallString {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        iter.into_iter().for_each(move |s| self.push_str(s));
+    }
+}
+
+impl FromIterator<char> for SmallString {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut string = SmallString::new();
+        string.extend(iter);
+        string
+    }
+}
+
+impl<'a> FromIterator<&'a str> for SmallString {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut string = SmallString::new();
+        string.extend(iter);
+        string
+    }
+}
+
+impl AsRef<str> for SmallString {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl AsRef<[u8]> for SmallString {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl Borrow<str> for SmallString {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+impl Display for SmallString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(self.as_str(), f)
+    }
+}
+
+impl Debug for SmallString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Hash for SmallString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl PartialEq for SmallString {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for SmallString {}
+
+impl PartialOrd for SmallString {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SmallString {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl_str_eq! { SmallString, str }
+impl_str_eq! { SmallString, &'a str }
+impl_str_eq! { SmallString, String }
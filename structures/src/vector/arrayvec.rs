@@ -0,0 +1,413 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::iter::FusedIterator;
+use std::mem::{self, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+use std::{ptr, slice};
+
+use super::iter::{Iter, IterMut};
+use super::rawvec::{handle_reserve, TryReserveError};
+
+use TryReserveError::*;
+
+/// Returned when an element doesn't fit into an `ArrayVec`, handing the element back.
+pub struct CapacityError<T = ()> {
+    element: T,
+}
+
+impl<T> CapacityError<T> {
+    pub const fn new(element: T) -> CapacityError<T> {
+        CapacityError { element }
+    }
+
+    pub fn element(self) -> T {
+        self.element
+    }
+}
+
+impl<T> Error for CapacityError<T> {}
+
+impl<T> Debug for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut builder = f.debug_struct("CapacityError");
+        builder.finish_non_exhaustive()
+    }
+}
+
+impl<T> Display for CapacityError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt("insufficient capacity", f)
+    }
+}
+
+pub struct ArrayVec<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> ArrayVec<T, N> {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    pub const fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    #[inline]
+    pub const fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.buf.as_ptr() as *const T
+    }
+
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.buf.as_mut_ptr() as *mut T
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+
+    /// # Safety
+    /// `len` must not exceed `N`, and the first `len` elements must be initialized.
+    #[inline]
+    pub unsafe fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= N);
+        self.len = len;
+    }
+
+    pub fn push(&mut self, value: T) -> Result<(), CapacityError<T>> {
+        if self.len == N {
+            return Err(CapacityError::new(value));
+        }
+
+        // SAFETY: len < N, so the slot at len is within the array
+        unsafe {
+            ptr::write(self.as_mut_ptr().add(self.len), value);
+        }
+        self.len += 1;
+        Ok(())
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.as_ptr().add(self.len))) }
+    }
+
+    pub fn insert(&mut self, index: usize, element: T) -> Result<(), CapacityError<T>> {
+        let len = self.len;
+        assert!(index <= len, "index out of bounds");
+
+        if len == N {
+            return Err(CapacityError::new(element));
+        }
+
+        // SAFETY: index <= len < N, so shifting the tail by one stays within the array
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            if index < len {
+                ptr::copy(p, p.add(1), len - index);
+            }
+            ptr::write(p, element);
+        }
+        self.len = len + 1;
+        Ok(())
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(index < len, "index out of bounds");
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            let value = ptr::read(p);
+            ptr::copy(p.add(1), p, len - index - 1);
+            self.len = len - 1;
+            value
+        }
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(index < len, "index out of bounds");
+
+        unsafe {
+            let base_ptr = self.as_mut_ptr();
+            let value = ptr::read(base_ptr.add(index));
+            ptr::copy(base_ptr.add(len - 1), base_ptr.add(index), 1);
+            self.len = len - 1;
+            value
+        }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve(additional));
+    }
+
+    pub fn reserve_exact(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve_exact(additional));
+    }
+
+    /// Checks that `additional` more elements fit, since the capacity can never grow.
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if additional > self.remaining_capacity() {
+            Err(CapacityOverflow)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.try_reserve(additional)
+    }
+
+    #[inline]
+    pub fn clear(&mut self) {
+        let elems: *mut [T] = self.as_mut_slice();
+        unsafe {
+            self.len = 0;
+            ptr::drop_in_place(elems);
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self)
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self)
+    }
+
+    /// Returns the inner array if it is full, or gives the `ArrayVec` back otherwise.
+    pub fn into_inner(self) -> Result<[T; N], Self> {
+        if self.len < N {
+            return Err(self);
+        }
+        let this = mem::ManuallyDrop::new(self);
+        // SAFETY: all N elements are initialized, and this is never dropped
+        unsafe { Ok(ptr::read(this.as_ptr() as *const [T; N])) }
+    }
+}
+
+impl<T, const N: usize> Deref for ArrayVec<T, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for ArrayVec<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Default for ArrayVec<T, N> {
+    #[inline]
+    fn default() -> Self {
+        ArrayVec::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayVec<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for ArrayVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for ArrayVec<T, N> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for ArrayVec<T, N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> Extend<T> for ArrayVec<T, N> {
+    /// # Panics
+    /// Panics if the iterator yields more elements than the remaining capacity.
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for element in iter {
+            if self.push(element).is_err() {
+                panic!("capacity overflow");
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for ArrayVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = ArrayVec::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<T, const N: usize> From<[T; N]> for ArrayVec<T, N> {
+    fn from(array: [T; N]) -> Self {
+        let array = mem::ManuallyDrop::new(array);
+        let mut v = ArrayVec::new();
+        // SAFETY: the elements are moved into v, and array is never dropped
+        unsafe {
+            ptr::copy_nonoverlapping(array.as_ptr(), v.as_mut_ptr(), N);
+            v.set_len(N);
+        }
+        v
+    }
+}
+
+impl<T, const N: usize> Drop for ArrayVec<T, N> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+impl<T, const N: usize> IntoIterator for ArrayVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        // the iterator owns the elements now, so the ArrayVec only keeps the storage
+        let end = mem::replace(&mut self.len, 0);
+        IntoIter {
+            data: self,
+            current: 0,
+            end,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a ArrayVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut ArrayVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct IntoIter<T, const N: usize> {
+    data: ArrayVec<T, N>,
+    current: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data.as_ptr().add(self.current), self.len()) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let len = self.len();
+        unsafe { slice::from_raw_parts_mut(self.data.as_mut_ptr().add(self.current), len) }
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.current == self.end {
+            None
+        } else {
+            let old = self.current;
+            self.current += 1;
+            unsafe { Some(ptr::read(self.data.as_ptr().add(old))) }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.current;
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.current == self.end {
+            None
+        } else {
+            self.end -= 1;
+            unsafe { Some(ptr::read(self.data.as_ptr().add(self.end))) }
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T: Debug, const N: usize> Debug for IntoIter<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
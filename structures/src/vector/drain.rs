@@ -0,0 +1,157 @@
+use std::alloc::{Allocator, Global};
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+use std::ptr::{self, NonNull};
+use std::{mem, slice};
+
+use super::growth::{Doubling, GrowthPolicy};
+use super::vec::Vec;
+
+pub struct Drain<'a, T: 'a, A: Allocator + 'a = Global, G: GrowthPolicy = Doubling> {
+    // start and length of the elements kept after the drained range
+    pub(super) tail_start: usize,
+    pub(super) tail_len: usize,
+    // elements of the drained range that have not been yielded yet
+    pub(super) iter: slice::Iter<'a, T>,
+    pub(super) vec: NonNull<Vec<T, A, G>>,
+}
+
+impl<'a, T, A: Allocator, G: GrowthPolicy> Drain<'a, T, A, G> {
+    pub fn as_slice(&self) -> &[T] {
+        self.iter.as_slice()
+    }
+
+    pub fn allocator(&self) -> &A {
+        unsafe { self.vec.as_ref().allocator() }
+    }
+
+    /// Writes elements from `replace_with` into the gap between the vector's length and
+    /// `tail_start`. Returns `true` if the gap was filled completely.
+    ///
+    /// # Safety
+    /// The drained range must have already been dropped or moved out.
+    pub(super) unsafe fn fill<I: Iterator<Item = T>>(&mut self, replace_with: &mut I) -> bool {
+        let vec = unsafe { self.vec.as_mut() };
+        let range_start = vec.len();
+        let range_end = self.tail_start;
+
+        // SAFETY: the gap lies within the allocation, since tail_start <= capacity
+        let gap = unsafe { vec.as_mut_ptr().add(range_start) };
+        for offset in 0..range_end - range_start {
+            match replace_with.next() {
+                Some(element) => unsafe {
+                    ptr::write(gap.add(offset), element);
+                    vec.set_len(range_start + offset + 1);
+                },
+                None => return false,
+            }
+        }
+        true
+    }
+
+    /// Makes room for `additional` more elements before the tail by moving it back.
+    ///
+    /// # Safety
+    /// The drained range must have already been dropped or moved out.
+    pub(super) unsafe fn move_tail(&mut self, additional: usize) {
+        let vec = unsafe { self.vec.as_mut() };
+        let len = self.tail_start + self.tail_len;
+        vec.buf.reserve(len, additional);
+
+        let new_tail_start = self.tail_start + additional;
+        unsafe {
+            let src = vec.as_ptr().add(self.tail_start);
+            let dst = vec.as_mut_ptr().add(new_tail_start);
+            ptr::copy(src, dst, self.tail_len);
+        }
+        self.tail_start = new_tail_start;
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> AsRef<[T]> for Drain<'_, T, A, G> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Iterator for Drain<'_, T, A, G> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        self.iter.next().map(|element| unsafe { ptr::read(element) })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for Drain<'_, T, A, G> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        self.iter.next_back().map(|element| unsafe { ptr::read(element) })
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> ExactSizeIterator for Drain<'_, T, A, G> {}
+
+impl<T, A: Allocator, G: GrowthPolicy> FusedIterator for Drain<'_, T, A, G> {}
+
+impl<T: Debug, A: Allocator, G: GrowthPolicy> Debug for Drain<'_, T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.iter.as_slice()).finish()
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for Drain<'_, T, A, G> {
+    fn drop(&mut self) {
+        // moves the tail back into place, even if dropping an element panics
+        struct DropGuard<'r, 'a, T, A: Allocator, G: GrowthPolicy>(&'r mut Drain<'a, T, A, G>);
+
+        impl<T, A: Allocator, G: GrowthPolicy> Drop for DropGuard<'_, '_, T, A, G> {
+            fn drop(&mut self) {
+                let drain = &mut *self.0;
+                if drain.tail_len > 0 {
+                    unsafe {
+                        let vec = drain.vec.as_mut();
+                        let start = vec.len();
+                        if drain.tail_start != start {
+                            let src = vec.as_ptr().add(drain.tail_start);
+                            let dst = vec.as_mut_ptr().add(start);
+                            ptr::copy(src, dst, drain.tail_len);
+                        }
+                        vec.set_len(start + drain.tail_len);
+                    }
+                }
+            }
+        }
+
+        let iter = mem::take(&mut self.iter);
+        let drop_len = iter.len();
+        let mut vec = self.vec;
+        let _guard = DropGuard(self);
+        if drop_len == 0 {
+            return;
+        }
+
+        // re-derive the remaining elements from the vector's pointer, since the slice
+        // iterator only grants shared access
+        unsafe {
+            let vec_ptr = vec.as_mut().as_mut_ptr();
+            let offset = if is_zst::<T>() {
+                0
+            } else {
+                iter.as_slice().as_ptr().offset_from(vec_ptr) as usize
+            };
+            let to_drop = ptr::slice_from_raw_parts_mut(vec_ptr.add(offset), drop_len);
+            ptr::drop_in_place(to_drop);
+        }
+    }
+}
+
+#[inline(always)]
+const fn is_zst<T>() -> bool {
+    mem::size_of::<T>() == 0
+}
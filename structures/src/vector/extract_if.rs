@@ -0,0 +1,90 @@
+use std::alloc::{Allocator, Global};
+use std::fmt::{self, Debug};
+use std::ptr;
+
+use super::growth::{Doubling, GrowthPolicy};
+use super::vec::Vec;
+
+pub struct ExtractIf<'a, T, F, A: Allocator = Global, G: GrowthPolicy = Doubling>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    pub(super) vec: &'a mut Vec<T, A, G>,
+    // index of the next element to inspect
+    pub(super) idx: usize,
+    // number of elements extracted so far
+    pub(super) del: usize,
+    // length of the vector before extraction started
+    pub(super) old_len: usize,
+    pub(super) pred: F,
+}
+
+impl<T, F, A: Allocator, G: GrowthPolicy> ExtractIf<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    pub fn allocator(&self) -> &A {
+        self.vec.allocator()
+    }
+}
+
+impl<T, F, A: Allocator, G: GrowthPolicy> Iterator for ExtractIf<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        unsafe {
+            while self.idx < self.old_len {
+                let p = self.vec.as_mut_ptr();
+                let i = self.idx;
+                // if the predicate panics, idx is not advanced and the element is kept
+                let extracted = (self.pred)(&mut *p.add(i));
+                self.idx += 1;
+                if extracted {
+                    self.del += 1;
+                    return Some(ptr::read(p.add(i)));
+                } else if self.del > 0 {
+                    // shift kept elements back over the holes left by extracted ones
+                    ptr::copy_nonoverlapping(p.add(i), p.add(i - self.del), 1);
+                }
+            }
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.old_len - self.idx))
+    }
+}
+
+impl<T: Debug, F, A: Allocator, G: GrowthPolicy> Debug for ExtractIf<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let remaining = unsafe {
+            std::slice::from_raw_parts(self.vec.as_ptr().add(self.idx), self.old_len - self.idx)
+        };
+        f.debug_tuple("ExtractIf").field(&remaining).finish()
+    }
+}
+
+impl<T, F, A: Allocator, G: GrowthPolicy> Drop for ExtractIf<'_, T, F, A, G>
+where
+    F: FnMut(&mut T) -> bool,
+{
+    fn drop(&mut self) {
+        unsafe {
+            // move the elements that were never inspected back over the holes
+            if self.idx < self.old_len && self.del > 0 {
+                let p = self.vec.as_mut_ptr();
+                let src = p.add(self.idx);
+                let dst = src.sub(self.del);
+                ptr::copy(src, dst, self.old_len - self.idx);
+            }
+            self.vec.set_len(self.old_len - self.del);
+        }
+    }
+}
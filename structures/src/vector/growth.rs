@@ -0,0 +1,56 @@
+/// Decides how much a `RawVec` grows when it runs out of space.
+///
+/// Policies only suggest a capacity. `RawVec` still clamps the suggestion to at least
+/// the required capacity and at most what fits into an `isize::MAX` byte layout.
+pub trait GrowthPolicy {
+    /// Returns the capacity to grow to from `cap`, when at least `required` is needed.
+    fn grow(cap: usize, required: usize) -> usize;
+
+    /// Returns the smallest non-zero capacity for elements of `elem_size` bytes.
+    #[inline]
+    fn min_non_zero_cap(elem_size: usize) -> usize {
+        match elem_size {
+            1 => 8,
+            d if d <= 1024 => 4,
+            _ => 1,
+        }
+    }
+}
+
+/// Doubles the capacity, amortizing pushes to O(1) at the cost of up to 50% slack.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Doubling;
+
+impl GrowthPolicy for Doubling {
+    #[inline]
+    fn grow(cap: usize, _required: usize) -> usize {
+        cap.saturating_mul(2)
+    }
+}
+
+/// Grows the capacity by half, trading more reallocations for less unused memory.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OneAndHalf;
+
+impl GrowthPolicy for OneAndHalf {
+    #[inline]
+    fn grow(cap: usize, _required: usize) -> usize {
+        cap.saturating_add(cap / 2)
+    }
+}
+
+/// Grows the capacity by `STEP` elements at a time, so pushes are no longer amortized O(1).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FixedIncrement<const STEP: usize>;
+
+impl<const STEP: usize> GrowthPolicy for FixedIncrement<STEP> {
+    #[inline]
+    fn grow(cap: usize, _required: usize) -> usize {
+        cap.saturating_add(STEP)
+    }
+
+    #[inline]
+    fn min_non_zero_cap(_elem_size: usize) -> usize {
+        STEP
+    }
+}
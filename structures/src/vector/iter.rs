@@ -1,16 +1,26 @@
+use std::alloc::{Allocator, Global};
 use std::fmt::Debug;
+use std::iter::{FusedIterator, TrustedLen};
+use std::mem::ManuallyDrop;
 use std::{marker::PhantomData, ptr::NonNull};
 use std::{mem, ptr, slice};
 
-pub struct IntoIter<T> {
+use super::rawvec::RawVec;
+
+pub struct IntoIter<T, A: Allocator = Global> {
     pub(super) buf: NonNull<T>,
     pub(super) phantom: PhantomData<T>,
     pub(super) cap: usize,
+    pub(super) alloc: ManuallyDrop<A>,
     pub(super) ptr: *const T,
+    // for ZSTs, end is ptr offset by len bytes, so that len can still be tracked
     pub(super) end: *const T,
 }
 
-impl<T> IntoIter<T> {
+unsafe impl<T: Send, A: Allocator + Send> Send for IntoIter<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for IntoIter<T, A> {}
+
+impl<T, A: Allocator> IntoIter<T, A> {
     pub fn as_raw_mut_slice(&mut self) -> *mut [T] {
         ptr::slice_from_raw_parts_mut(self.ptr as *mut T, self.len())
     }
@@ -22,17 +32,32 @@ impl<T> IntoIter<T> {
     pub fn as_mut_slice(&mut self) -> &mut [T] {
         unsafe { &mut *self.as_raw_mut_slice() }
     }
+
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    /// Forgets the remaining elements without dropping them, the buffer is still freed.
+    pub(super) fn forget_remaining_elements(&mut self) {
+        self.end = self.ptr;
+    }
 }
 
-impl<T> AsRef<[T]> for IntoIter<T> {
+impl<T, A: Allocator> AsRef<[T]> for IntoIter<T, A> {
     fn as_ref(&self) -> &[T] {
         self.as_slice()
     }
 }
 
-impl<T> ExactSizeIterator for IntoIter<T> {}
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
 
-impl<T> Iterator for IntoIter<T> {
+// SAFETY: size_hint is always exact, since it is computed from ptr and end
+unsafe impl<T, A: Allocator> TrustedLen for IntoIter<T, A> {}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
     type Item = T;
 
     #[inline]
@@ -40,8 +65,9 @@ impl<T> Iterator for IntoIter<T> {
         if self.ptr == self.end {
             None
         } else if is_zst::<T>() {
-            self.end = self.end.wrapping_offset((1 as isize).wrapping_neg());
-            Some(unsafe { mem::zeroed() })
+            // ZSTs don't move ptr, since any aligned non-null pointer is valid for them
+            self.end = self.end.wrapping_byte_sub(1);
+            Some(unsafe { ptr::read(self.ptr) })
         } else {
             let old = self.ptr;
             self.ptr = unsafe { self.ptr.add(1) };
@@ -49,28 +75,39 @@ impl<T> Iterator for IntoIter<T> {
         }
     }
 
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if is_zst::<T>() {
+            (self.end as usize).wrapping_sub(self.ptr as usize)
+        } else {
+            // SAFETY: ptr and end are in the same allocation, and ptr <= end
+            unsafe { self.end.offset_from(self.ptr) as usize }
+        };
+        (len, Some(len))
+    }
+
     #[inline]
     fn count(self) -> usize {
         self.len()
     }
 }
 
-impl<T: Debug> Debug for IntoIter<T> {
+impl<T: Debug, A: Allocator> Debug for IntoIter<T, A> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
     }
 }
 
-impl<T> DoubleEndedIterator for IntoIter<T> {
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
     #[inline]
     fn next_back(&mut self) -> Option<Self::Item> {
         if self.ptr == self.end {
             None
         } else if is_zst::<T>() {
-            self.end = self.end.wrapping_offset((1 as isize).wrapping_neg());
-            Some(unsafe { mem::zeroed() })
+            self.end = self.end.wrapping_byte_sub(1);
+            Some(unsafe { ptr::read(self.ptr) })
         } else {
-            self.end = unsafe { self.ptr.sub(1) };
+            self.end = unsafe { self.end.sub(1) };
             Some(unsafe { ptr::read(self.end) })
         }
     }
@@ -78,13 +115,257 @@ impl<T> DoubleEndedIterator for IntoIter<T> {
 
 impl<T> Default for IntoIter<T> {
     fn default() -> Self {
-        super::Vec::new().into_iter()
+        super::Vec::<T>::new().into_iter()
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for IntoIter<T, A> {
+    fn clone(&self) -> Self {
+        let alloc = ManuallyDrop::into_inner(self.alloc.clone());
+        let mut vec = super::Vec::<T, A>::with_capacity_in(self.len(), alloc);
+        vec.extend(self.as_slice().iter().cloned());
+        vec.into_iter()
+    }
+}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        // frees the buffer, even if dropping one of the remaining elements panics
+        struct DropGuard<'a, T, A: Allocator>(&'a mut IntoIter<T, A>);
+
+        impl<T, A: Allocator> Drop for DropGuard<'_, T, A> {
+            fn drop(&mut self) {
+                unsafe {
+                    let alloc = ManuallyDrop::take(&mut self.0.alloc);
+                    // RawVec handles deallocation
+                    let _ = RawVec::<T, A>::from_raw_parts_in(self.0.buf.as_ptr(), self.0.cap, alloc);
+                }
+            }
+        }
+
+        let guard = DropGuard(self);
+        unsafe {
+            ptr::drop_in_place(guard.0.as_raw_mut_slice());
+        }
+    }
+}
+
+pub struct Iter<'a, T: 'a> {
+    ptr: NonNull<T>,
+    // for ZSTs, end is ptr offset by len bytes, so that len can still be tracked
+    end: *const T,
+    marker: PhantomData<&'a T>,
+}
+
+unsafe impl<T: Sync> Send for Iter<'_, T> {}
+unsafe impl<T: Sync> Sync for Iter<'_, T> {}
+
+impl<'a, T> Iter<'a, T> {
+    #[inline]
+    pub(super) fn new(slice: &'a [T]) -> Self {
+        let (ptr, end) = slice_bounds(slice.as_ptr() as *mut T, slice.len());
+        Iter {
+            ptr,
+            end,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn as_slice(&self) -> &'a [T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len()) }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a T> {
+        // SAFETY: ptr is within bounds until it reaches end
+        unsafe { next_ptr(&mut self.ptr, &mut self.end).map(|p| &*p) }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = bounds_len(self.ptr, self.end);
+        (len, Some(len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
     }
 }
 
-impl<T: Clone> Clone for IntoIter<T> {
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a T> {
+        // SAFETY: end - 1 is within bounds until it reaches ptr
+        unsafe { next_back_ptr(&mut self.ptr, &mut self.end).map(|p| &*p) }
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+unsafe impl<T> TrustedLen for Iter<'_, T> {}
+
+impl<T> Clone for Iter<'_, T> {
     fn clone(&self) -> Self {
-        todo!()
+        Iter { ..*self }
+    }
+}
+
+impl<T> Default for Iter<'_, T> {
+    fn default() -> Self {
+        Iter::new(&[])
+    }
+}
+
+impl<T> AsRef<[T]> for Iter<'_, T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: Debug> Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Iter").field(&self.as_slice()).finish()
+    }
+}
+
+pub struct IterMut<'a, T: 'a> {
+    ptr: NonNull<T>,
+    // for ZSTs, end is ptr offset by len bytes, so that len can still be tracked
+    end: *const T,
+    marker: PhantomData<&'a mut T>,
+}
+
+unsafe impl<T: Send> Send for IterMut<'_, T> {}
+unsafe impl<T: Sync> Sync for IterMut<'_, T> {}
+
+impl<'a, T> IterMut<'a, T> {
+    #[inline]
+    pub(super) fn new(slice: &'a mut [T]) -> Self {
+        let (ptr, end) = slice_bounds(slice.as_mut_ptr(), slice.len());
+        IterMut {
+            ptr,
+            end,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len()) }
+    }
+
+    pub fn into_slice(self) -> &'a mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len()) }
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a mut T> {
+        // SAFETY: ptr is within bounds until it reaches end, and every element is yielded once
+        unsafe { next_ptr(&mut self.ptr, &mut self.end).map(|p| &mut *p) }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = bounds_len(self.ptr, self.end);
+        (len, Some(len))
+    }
+
+    #[inline]
+    fn count(self) -> usize {
+        self.len()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        // SAFETY: end - 1 is within bounds until it reaches ptr, and every element is yielded once
+        unsafe { next_back_ptr(&mut self.ptr, &mut self.end).map(|p| &mut *p) }
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+unsafe impl<T> TrustedLen for IterMut<'_, T> {}
+
+impl<T> Default for IterMut<'_, T> {
+    fn default() -> Self {
+        IterMut::new(&mut [])
+    }
+}
+
+impl<T> AsRef<[T]> for IterMut<'_, T> {
+    fn as_ref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T: Debug> Debug for IterMut<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("IterMut").field(&self.as_slice()).finish()
+    }
+}
+
+#[inline]
+fn slice_bounds<T>(ptr: *mut T, len: usize) -> (NonNull<T>, *const T) {
+    // SAFETY: slice pointers are never null
+    let start = unsafe { NonNull::new_unchecked(ptr) };
+    let end = if is_zst::<T>() {
+        ptr.wrapping_byte_add(len) as *const T
+    } else {
+        // SAFETY: one past the end of a slice is still in bounds of its allocation
+        unsafe { ptr.add(len) as *const T }
+    };
+    (start, end)
+}
+
+#[inline]
+fn bounds_len<T>(ptr: NonNull<T>, end: *const T) -> usize {
+    if is_zst::<T>() {
+        (end as usize).wrapping_sub(ptr.as_ptr() as usize)
+    } else {
+        // SAFETY: ptr and end are derived from the same slice, and ptr <= end
+        unsafe { end.offset_from(ptr.as_ptr()) as usize }
+    }
+}
+
+#[inline]
+unsafe fn next_ptr<T>(ptr: &mut NonNull<T>, end: &mut *const T) -> Option<*mut T> {
+    if ptr::eq(ptr.as_ptr(), *end) {
+        None
+    } else if is_zst::<T>() {
+        // ZSTs don't move ptr, since any aligned non-null pointer is valid for them
+        *end = end.wrapping_byte_sub(1);
+        Some(ptr.as_ptr())
+    } else {
+        let old = *ptr;
+        *ptr = unsafe { ptr.add(1) };
+        Some(old.as_ptr())
+    }
+}
+
+#[inline]
+unsafe fn next_back_ptr<T>(ptr: &mut NonNull<T>, end: &mut *const T) -> Option<*mut T> {
+    if ptr::eq(ptr.as_ptr(), *end) {
+        None
+    } else if is_zst::<T>() {
+        *end = end.wrapping_byte_sub(1);
+        Some(ptr.as_ptr())
+    } else {
+        *end = unsafe { end.sub(1) };
+        Some(*end as *mut T)
     }
 }
 
@@ -1,7 +1,22 @@
 mod rawvec;
 mod vec;
 mod iter;
+mod drain;
+mod splice;
+mod extract_if;
+mod growth;
+mod sort;
+mod spec_extend;
+mod spec_from_iter;
+pub mod smallvec;
+pub mod arrayvec;
 
 pub use vec::Vec;
-pub use rawvec::RawVec;
-pub use iter::IntoIter;
\ No newline at end of file
+pub use rawvec::{RawPartsError, RawVec, TryReserveError};
+pub use iter::{IntoIter, Iter, IterMut};
+pub use drain::Drain;
+pub use splice::Splice;
+pub use extract_if::ExtractIf;
+pub use growth::{Doubling, FixedIncrement, GrowthPolicy, OneAndHalf};
+pub use smallvec::SmallVec;
+pub use arrayvec::{ArrayVec, CapacityError};
\ No newline at end of file
@@ -1,52 +1,202 @@
 use std::alloc::handle_alloc_error;
-use std::alloc::Layout;
-use std::ptr::NonNull;
+use std::alloc::{Allocator, Global, Layout};
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
+use std::ptr::{self, NonNull};
 use std::{alloc, cmp, mem};
 
+use super::growth::{Doubling, GrowthPolicy};
+
 use TryReserveError::*;
 
+/// Returned by the fallible allocation methods instead of panicking or aborting.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TryReserveError {
+    /// The capacity would exceed `isize::MAX` bytes, or overflow `usize`.
     CapacityOverflow,
+    /// The allocator failed to allocate `layout`.
     AllocError { layout: alloc::Layout },
 }
 
+impl Error for TryReserveError {}
+
+impl Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("memory allocation failed")?;
+        match self {
+            CapacityOverflow => {
+                f.write_str(" because the computed capacity exceeded the collection's maximum")
+            }
+            AllocError { layout } => write!(
+                f,
+                " because the allocator failed for {} bytes aligned to {}",
+                layout.size(),
+                layout.align()
+            ),
+        }
+    }
+}
+
+/// Describes why a pointer and capacity can't be the raw parts of a `RawVec`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawPartsError {
+    Null,
+    Misaligned { align: usize },
+    LayoutOverflow { capacity: usize },
+    LengthExceedsCapacity { len: usize, capacity: usize },
+}
+
+impl Error for RawPartsError {}
+
+impl Display for RawPartsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            RawPartsError::Null => f.write_str("pointer is null"),
+            RawPartsError::Misaligned { align } => {
+                write!(f, "pointer is not aligned to {align} bytes")
+            }
+            RawPartsError::LayoutOverflow { capacity } => {
+                write!(
+                    f,
+                    "a capacity of {capacity} exceeds the maximum allocation size"
+                )
+            }
+            RawPartsError::LengthExceedsCapacity { len, capacity } => {
+                write!(f, "length {len} exceeds capacity {capacity}")
+            }
+        }
+    }
+}
+
 enum AllocInit {
     Uninitialized,
     Zeroed,
 }
 
-pub struct RawVec<T> {
+pub struct RawVec<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
     ptr: NonNull<T>,
     cap: usize,
+    alloc: A,
+    policy: PhantomData<G>,
 }
 
-unsafe impl<T: Send> Send for RawVec<T> {}
-unsafe impl<T: Sync> Sync for RawVec<T> {}
-
-impl<T> RawVec<T> {
-    pub(crate) const MIN_NON_ZERO_CAP: usize = match mem::size_of::<T>() {
-        d if d == 1 => 8,
-        d if d <= 1024 => 4,
-        _ => 1,
-    };
+unsafe impl<T: Send, A: Allocator + Send, G: GrowthPolicy> Send for RawVec<T, A, G> {}
+unsafe impl<T: Sync, A: Allocator + Sync, G: GrowthPolicy> Sync for RawVec<T, A, G> {}
 
+impl<T, G: GrowthPolicy> RawVec<T, Global, G> {
     pub const NEW: Self = Self::new();
 
     pub const fn new() -> Self {
-        Self {
-            ptr: NonNull::<T>::dangling(),
-            cap: 0,
-        }
+        Self::new_in(Global)
     }
 
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
-        Self::allocate_new(capacity, AllocInit::Uninitialized)
+        Self::with_capacity_in(capacity, Global)
     }
 
     #[inline]
     pub fn with_capacity_zeroed(capacity: usize) -> Self {
-        Self::allocate_new(capacity, AllocInit::Zeroed)
+        Self::with_capacity_zeroed_in(capacity, Global)
+    }
+
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_in(capacity, Global)
+    }
+
+    /// # Safety
+    /// See [`RawVec::from_raw_parts_in`].
+    pub unsafe fn from_raw_parts(value: *mut T, capacity: usize) -> Self {
+        unsafe { Self::from_raw_parts_in(value, capacity, Global) }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> RawVec<T, A, G> {
+    pub const fn new_in(alloc: A) -> Self {
+        Self::dangling_in(alloc)
+    }
+
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::allocate_new(capacity, AllocInit::Uninitialized, alloc)
+    }
+
+    #[inline]
+    pub fn with_capacity_zeroed_in(capacity: usize, alloc: A) -> Self {
+        Self::allocate_new(capacity, AllocInit::Zeroed, alloc)
+    }
+
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
+        Self::try_allocate_new(capacity, AllocInit::Uninitialized, alloc)
+    }
+
+    /// Rebuilds a `RawVec` from a pointer and capacity, taking ownership of the allocation.
+    /// Debug builds check the parts with [`RawVec::check_raw_parts`] and panic if they are invalid.
+    ///
+    /// # Safety
+    /// `value` must have been allocated by `alloc` with the layout of `capacity` elements of `T`,
+    /// or be dangling and well aligned if `capacity` is zero or `T` is zero sized.
+    pub unsafe fn from_raw_parts_in(value: *mut T, capacity: usize, alloc: A) -> Self {
+        if cfg!(debug_assertions) {
+            if let Err(err) = Self::check_raw_parts(value, capacity) {
+                panic!("invalid raw parts: {err}");
+            }
+        }
+        Self {
+            ptr: unsafe { NonNull::new_unchecked(value) },
+            cap: capacity,
+            alloc,
+            policy: PhantomData,
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> RawVec<T, A, G> {
+    /// Checks the parts that can be checked without knowing where the pointer came from:
+    /// it is not null, it is aligned for `T`, and `capacity` elements fit into a `Layout`.
+    pub fn check_raw_parts(ptr: *const T, capacity: usize) -> Result<(), RawPartsError> {
+        if ptr.is_null() {
+            return Err(RawPartsError::Null);
+        }
+        if !ptr.is_aligned() {
+            return Err(RawPartsError::Misaligned {
+                align: mem::align_of::<T>(),
+            });
+        }
+        if Layout::array::<T>(capacity).is_err() {
+            return Err(RawPartsError::LayoutOverflow { capacity });
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn min_non_zero_cap() -> usize {
+        G::min_non_zero_cap(mem::size_of::<T>())
+    }
+
+    const fn dangling_in(alloc: A) -> Self {
+        Self {
+            ptr: NonNull::<T>::dangling(),
+            cap: 0,
+            alloc,
+            policy: PhantomData,
+        }
+    }
+
+    /// Switches to another growth policy, keeping the same allocation.
+    pub fn into_policy<H: GrowthPolicy>(self) -> RawVec<T, A, H> {
+        let m = ManuallyDrop::new(self);
+        RawVec {
+            ptr: m.ptr,
+            cap: m.cap,
+            // SAFETY: the allocator is moved out, and m is never dropped
+            alloc: unsafe { ptr::read(&m.alloc) },
+            policy: PhantomData,
+        }
     }
 
     #[inline]
@@ -54,10 +204,15 @@ impl<T> RawVec<T> {
         self.ptr.as_ptr()
     }
 
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
     #[inline(always)]
     pub fn capacity(&self) -> usize {
         if is_zst::<T>() {
-            usize::MAX as usize
+            usize::MAX
         } else {
             self.cap
         }
@@ -78,28 +233,31 @@ impl<T> RawVec<T> {
         }
     }
 
-    fn memory(&self) -> Option<(*mut u8, Layout)> {
-        self.layout().map(|layout| (self.ptr() as *mut u8, layout))
+    fn memory(&self) -> Option<(NonNull<u8>, Layout)> {
+        self.layout().map(|layout| (self.ptr.cast(), layout))
     }
 
     #[inline(always)]
     fn handle_zst_overflow(&self) -> Result<(), TryReserveError> {
         if is_zst::<T>() {
-            return Err(CapacityOverflow);
+            Err(CapacityOverflow)
         } else {
             Ok(())
         }
     }
 
-    fn unchecked_set_ptr_and_cap(&mut self, ptr: *mut u8, cap: usize) {
-        // SAFETY: ptr must be non-null and valid
-        self.ptr = unsafe { NonNull::new_unchecked(ptr as *mut T) };
+    fn set_ptr_and_cap(&mut self, ptr: NonNull<[u8]>, cap: usize) {
+        self.ptr = ptr.cast();
         self.cap = cap;
     }
 
-    fn try_allocate_new(capacity: usize, init: AllocInit) -> Result<Self, TryReserveError> {
+    fn try_allocate_new(
+        capacity: usize,
+        init: AllocInit,
+        alloc: A,
+    ) -> Result<Self, TryReserveError> {
         if is_zst::<T>() || capacity == 0 {
-            return Ok(Self::new());
+            return Ok(Self::dangling_in(alloc));
         }
 
         let layout = Layout::array::<T>(capacity).map_err(|_| CapacityOverflow)?;
@@ -108,24 +266,25 @@ impl<T> RawVec<T> {
             return Err(CapacityOverflow);
         }
 
-        let ptr = match init {
-            AllocInit::Uninitialized => unsafe { alloc::alloc(layout) },
-            AllocInit::Zeroed => unsafe { alloc::alloc_zeroed(layout) },
+        let result = match init {
+            AllocInit::Uninitialized => alloc.allocate(layout),
+            AllocInit::Zeroed => alloc.allocate_zeroed(layout),
         };
 
-        // if an allocation error occurred, pointer would be null
-        if ptr.is_null() {
-            Err(AllocError { layout })
-        } else {
-            Ok(Self {
-                ptr: unsafe { NonNull::new_unchecked(ptr as *mut T) },
+        // the allocator reports failure instead of returning a null pointer
+        match result {
+            Ok(ptr) => Ok(Self {
+                ptr: ptr.cast(),
                 cap: capacity,
-            })
+                alloc,
+                policy: PhantomData,
+            }),
+            Err(_) => Err(AllocError { layout }),
         }
     }
 
-    fn allocate_new(capacity: usize, init: AllocInit) -> Self {
-        handle_reserve_unwrap(Self::try_allocate_new(capacity, init))
+    fn allocate_new(capacity: usize, init: AllocInit, alloc: A) -> Self {
+        handle_reserve_unwrap(Self::try_allocate_new(capacity, init, alloc))
     }
 
     fn checked_alloc_cap(&mut self, cap: usize) -> Result<(), TryReserveError> {
@@ -137,18 +296,21 @@ impl<T> RawVec<T> {
         }
 
         // get pointer to allocated/reallocated memory
-        // SAFETY: layout and size are ensured to be valid by Layout::array
-        let ptr = if let Some(old_layout) = self.layout() {
-            unsafe { alloc::realloc(self.ptr() as *mut u8, old_layout, layout.size()) }
+        // SAFETY: layout and size are ensured to be valid by Layout::array,
+        // and the old memory block was allocated by this allocator with old_layout
+        let result = if let Some((ptr, old_layout)) = self.memory() {
+            unsafe { self.alloc.grow(ptr, old_layout, layout) }
         } else {
-            unsafe { alloc::alloc(layout) }
+            self.alloc.allocate(layout)
         };
 
-        // if an allocation error occurred, pointer would be null
-        if ptr.is_null() {
-            Err(AllocError { layout })
-        } else {
-            Ok(self.unchecked_set_ptr_and_cap(ptr, cap))
+        // the allocator reports failure instead of returning a null pointer
+        match result {
+            Ok(ptr) => {
+                self.set_ptr_and_cap(ptr, cap);
+                Ok(())
+            }
+            Err(_) => Err(AllocError { layout }),
         }
     }
 
@@ -162,10 +324,13 @@ impl<T> RawVec<T> {
         self.handle_zst_overflow()?;
 
         // calculate capacity based on additional required
-        // cap <= isize::MAX, so it doesn't overflow usize
         let required_cap = len.checked_add(additional).ok_or(CapacityOverflow)?;
-        let cap = cmp::max(self.cap * 2, required_cap);
-        let cap = cmp::max(Self::MIN_NON_ZERO_CAP, cap);
+
+        // the policy's suggestion is clamped below the largest capacity allowed by the layout,
+        // so that a generous policy can't fail an allocation that the required capacity fits in
+        let max_cap = isize::MAX as usize / mem::size_of::<T>();
+        let cap = cmp::max(Self::min_non_zero_cap(), G::grow(self.cap, required_cap));
+        let cap = cmp::max(cmp::min(cap, max_cap), required_cap);
 
         // allocate given capacity
         self.checked_alloc_cap(cap)
@@ -219,18 +384,57 @@ impl<T> RawVec<T> {
         }
     }
 
-    pub fn from_raw_parts(value: *mut T, capacity: usize) -> RawVec<T> {
-        RawVec {
-            ptr: unsafe { NonNull::new_unchecked(value) },
-            cap: capacity,
+    fn try_shrink_to(&mut self, cap: usize) -> Result<(), TryReserveError> {
+        assert!(
+            cap <= self.capacity(),
+            "tried to shrink to a larger capacity"
+        );
+
+        // zero sized types and empty buffers have no memory to give back
+        let Some((ptr, old_layout)) = self.memory() else {
+            return Ok(());
+        };
+
+        if cap == 0 {
+            // SAFETY: the memory block was allocated by this allocator with old_layout
+            unsafe { self.alloc.deallocate(ptr, old_layout) };
+            self.ptr = NonNull::dangling();
+            self.cap = 0;
+            return Ok(());
+        }
+
+        // SAFETY: cap < old cap, so the new layout is valid and not larger than the old one
+        let layout = unsafe {
+            Layout::from_size_align_unchecked(mem::size_of::<T>() * cap, old_layout.align())
+        };
+        match unsafe { self.alloc.shrink(ptr, old_layout, layout) } {
+            Ok(ptr) => {
+                self.set_ptr_and_cap(ptr, cap);
+                Ok(())
+            }
+            Err(_) => Err(AllocError { layout }),
         }
     }
+
+    /// Shrinks the allocation down to `cap` elements, or frees it if `cap` is zero.
+    ///
+    /// # Panics
+    /// Panics if `cap` is larger than the current capacity.
+    pub fn shrink_to(&mut self, cap: usize) {
+        handle_reserve(self.try_shrink_to(cap));
+    }
+}
+
+impl<T, G: GrowthPolicy> Default for RawVec<T, Global, G> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<T> Drop for RawVec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> Drop for RawVec<T, A, G> {
     fn drop(&mut self) {
         if let Some((ptr, layout)) = self.memory() {
-            unsafe { alloc::dealloc(ptr, layout) };
+            unsafe { self.alloc.deallocate(ptr, layout) };
         }
     }
 }
@@ -243,7 +447,7 @@ fn handle_reserve_unwrap<T>(result: Result<T, TryReserveError>) -> T {
     }
 }
 
-fn handle_reserve<T>(result: Result<T, TryReserveError>) {
+pub(super) fn handle_reserve<T>(result: Result<T, TryReserveError>) {
     handle_reserve_unwrap(result);
 }
 
@@ -0,0 +1,402 @@
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::ops::{Deref, DerefMut};
+use std::{cmp, mem, ptr, slice};
+
+use super::iter::{Iter, IterMut};
+use super::rawvec::{handle_reserve, RawVec, TryReserveError};
+use super::vec::Vec;
+
+use TryReserveError::*;
+
+enum SmallVecData<T, const N: usize> {
+    Inline(MaybeUninit<[T; N]>),
+    Heap(RawVec<T>),
+}
+
+pub struct SmallVec<T, const N: usize> {
+    data: SmallVecData<T, N>,
+    len: usize,
+}
+
+impl<T, const N: usize> SmallVec<T, N> {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            data: SmallVecData::Inline(MaybeUninit::uninit()),
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut v = Self::new();
+        v.reserve(capacity);
+        v
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        match &self.data {
+            SmallVecData::Inline(_) if is_zst::<T>() => usize::MAX,
+            SmallVecData::Inline(_) => N,
+            SmallVecData::Heap(buf) => buf.capacity(),
+        }
+    }
+
+    /// Returns `true` if the elements have been moved into a heap allocation.
+    #[inline]
+    pub fn spilled(&self) -> bool {
+        matches!(self.data, SmallVecData::Heap(_))
+    }
+
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        match &self.data {
+            SmallVecData::Inline(array) => array.as_ptr() as *const T,
+            SmallVecData::Heap(buf) => buf.ptr(),
+        }
+    }
+
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        match &mut self.data {
+            SmallVecData::Inline(array) => array.as_mut_ptr() as *mut T,
+            SmallVecData::Heap(buf) => buf.ptr(),
+        }
+    }
+
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        self
+    }
+
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        self
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        handle_reserve(self.try_reserve(additional));
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        if additional <= self.capacity() - self.len {
+            return Ok(());
+        }
+        match &mut self.data {
+            SmallVecData::Heap(buf) => buf.try_reserve(self.len, additional),
+            SmallVecData::Inline(_) => self.try_spill(additional),
+        }
+    }
+
+    fn try_spill(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        // at least double the inline capacity, so that spilling is followed by the usual growth
+        let required_cap = self.len.checked_add(additional).ok_or(CapacityOverflow)?;
+        let cap = cmp::max(required_cap, N.saturating_mul(2));
+        let mut buf = RawVec::new();
+        buf.try_reserve_exact(0, cap)?;
+
+        // SAFETY: buf is a new allocation that can hold at least len elements
+        unsafe { ptr::copy_nonoverlapping(self.as_ptr(), buf.ptr(), self.len) };
+        self.data = SmallVecData::Heap(buf);
+        Ok(())
+    }
+
+    pub fn push(&mut self, value: T) {
+        if self.len == self.capacity() {
+            self.reserve(1);
+        }
+
+        // SAFETY: len < capacity, so the slot at len is within the storage
+        unsafe {
+            ptr::write(self.as_mut_ptr().add(self.len), value);
+        }
+        self.len += 1;
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        unsafe { Some(ptr::read(self.as_ptr().add(self.len))) }
+    }
+
+    pub fn insert(&mut self, index: usize, element: T) {
+        let len = self.len;
+        assert!(index <= len, "index out of bounds");
+
+        if len == self.capacity() {
+            self.reserve(1);
+        }
+
+        // SAFETY: index <= len < capacity, so shifting the tail by one stays within the storage
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            if index < len {
+                ptr::copy(p, p.add(1), len - index);
+            }
+            ptr::write(p, element);
+        }
+        self.len = len + 1;
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(index < len, "index out of bounds");
+
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            let value = ptr::read(p);
+            ptr::copy(p.add(1), p, len - index - 1);
+            self.len = len - 1;
+            value
+        }
+    }
+
+    pub fn swap_remove(&mut self, index: usize) -> T {
+        let len = self.len;
+        assert!(index < len, "index out of bounds");
+
+        unsafe {
+            let base_ptr = self.as_mut_ptr();
+            let value = ptr::read(base_ptr.add(index));
+            ptr::copy(base_ptr.add(len - 1), base_ptr.add(index), 1);
+            self.len = len - 1;
+            value
+        }
+    }
+
+    pub fn clear(&mut self) {
+        let elems: *mut [T] = self.as_mut_slice();
+        unsafe {
+            self.len = 0;
+            ptr::drop_in_place(elems);
+        }
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self)
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self)
+    }
+
+    /// Converts into a `Vec`, reusing the heap allocation if the elements were spilled.
+    pub fn into_vec(self) -> Vec<T> {
+        let mut m = ManuallyDrop::new(self);
+        let len = m.len;
+        match &mut m.data {
+            SmallVecData::Heap(buf) => {
+                // SAFETY: the buffer is moved out, and m is never dropped
+                let buf = unsafe { ptr::read(buf) };
+                let cap = buf.capacity();
+                let buf = ManuallyDrop::new(buf);
+                unsafe { Vec::from_raw_parts(buf.ptr(), len, cap) }
+            }
+            SmallVecData::Inline(array) => {
+                let mut v = Vec::with_capacity(len);
+                unsafe {
+                    ptr::copy_nonoverlapping(array.as_ptr() as *const T, v.as_mut_ptr(), len);
+                    v.set_len(len);
+                }
+                v
+            }
+        }
+    }
+}
+
+impl<T, const N: usize> Deref for SmallVec<T, N> {
+    type Target = [T];
+
+    #[inline]
+    fn deref(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.as_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> DerefMut for SmallVec<T, N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [T] {
+        unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+    }
+}
+
+impl<T, const N: usize> Default for SmallVec<T, N> {
+    #[inline]
+    fn default() -> Self {
+        SmallVec::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallVec<T, N> {
+    fn clone(&self) -> Self {
+        self.iter().cloned().collect()
+    }
+}
+
+impl<T: Debug, const N: usize> Debug for SmallVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for SmallVec<T, N> {
+    #[inline]
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, const N: usize> AsMut<[T]> for SmallVec<T, N> {
+    #[inline]
+    fn as_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iterator = iter.into_iter();
+        let (lower, _) = iterator.size_hint();
+        self.reserve(lower);
+        for element in iterator {
+            self.push(element);
+        }
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut v = SmallVec::new();
+        v.extend(iter);
+        v
+    }
+}
+
+impl<T, const N: usize> Drop for SmallVec<T, N> {
+    fn drop(&mut self) {
+        // the heap buffer, if any, is freed by RawVec
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+impl<T, const N: usize> IntoIterator for SmallVec<T, N> {
+    type Item = T;
+    type IntoIter = IntoIter<T, N>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        // the iterator owns the elements now, so the SmallVec only keeps the storage
+        let end = mem::replace(&mut self.len, 0);
+        IntoIter {
+            data: self,
+            current: 0,
+            end,
+        }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+pub struct IntoIter<T, const N: usize> {
+    data: SmallVec<T, N>,
+    current: usize,
+    end: usize,
+}
+
+impl<T, const N: usize> IntoIter<T, N> {
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.data.as_ptr().add(self.current), self.len()) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        let len = self.len();
+        unsafe { slice::from_raw_parts_mut(self.data.as_mut_ptr().add(self.current), len) }
+    }
+}
+
+impl<T, const N: usize> Iterator for IntoIter<T, N> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.current == self.end {
+            None
+        } else {
+            let old = self.current;
+            self.current += 1;
+            unsafe { Some(ptr::read(self.data.as_ptr().add(old))) }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.current;
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for IntoIter<T, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<T> {
+        if self.current == self.end {
+            None
+        } else {
+            self.end -= 1;
+            unsafe { Some(ptr::read(self.data.as_ptr().add(self.end))) }
+        }
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for IntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for IntoIter<T, N> {}
+
+impl<T: Debug, const N: usize> Debug for IntoIter<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("IntoIter").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T, const N: usize> Drop for IntoIter<T, N> {
+    fn drop(&mut self) {
+        // drop the elements that were not yielded, then data frees the storage
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+#[inline(always)]
+const fn is_zst<T>() -> bool {
+    mem::size_of::<T>() == 0
+}
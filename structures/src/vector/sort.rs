@@ -0,0 +1,621 @@
+//! Stable merge sort and unstable pattern-defeating quicksort, after the versions `std` shipped
+//! before driftsort and ipnsort. Elements are moved around as raw bytes, so every place that
+//! calls the comparator while an element is out of the slice keeps a guard that puts it back.
+
+use std::mem::{self, ManuallyDrop};
+use std::{cmp, ptr};
+
+use super::rawvec::RawVec;
+use super::vec::Vec;
+
+/// Copies `src` into `dest` when dropped, filling the hole an element was read out of.
+struct CopyOnDrop<T> {
+    src: *const T,
+    dest: *mut T,
+}
+
+impl<T> Drop for CopyOnDrop<T> {
+    fn drop(&mut self) {
+        // SAFETY: the guard is only built over a valid element and the hole it came from
+        unsafe { ptr::copy_nonoverlapping(self.src, self.dest, 1) }
+    }
+}
+
+/// Shifts the first element to the right until it reaches a smaller or equal element.
+fn shift_head<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let len = v.len();
+    let v = v.as_mut_ptr();
+    unsafe {
+        if len >= 2 && is_less(&*v.add(1), &*v) {
+            // hold the first element aside, the hole moves right as elements shift left
+            let tmp = ManuallyDrop::new(ptr::read(v));
+            let mut hole = CopyOnDrop {
+                src: &*tmp,
+                dest: v.add(1),
+            };
+            ptr::copy_nonoverlapping(v.add(1), v, 1);
+
+            for i in 2..len {
+                if !is_less(&*v.add(i), &*tmp) {
+                    break;
+                }
+                ptr::copy_nonoverlapping(v.add(i), v.add(i - 1), 1);
+                hole.dest = v.add(i);
+            }
+            // hole is dropped here, writing tmp into the remaining hole
+        }
+    }
+}
+
+/// Shifts the last element to the left until it reaches a smaller or equal element.
+fn shift_tail<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let len = v.len();
+    let v = v.as_mut_ptr();
+    unsafe {
+        if len >= 2 && is_less(&*v.add(len - 1), &*v.add(len - 2)) {
+            let tmp = ManuallyDrop::new(ptr::read(v.add(len - 1)));
+            let mut hole = CopyOnDrop {
+                src: &*tmp,
+                dest: v.add(len - 2),
+            };
+            ptr::copy_nonoverlapping(v.add(len - 2), v.add(len - 1), 1);
+
+            for i in (0..len - 2).rev() {
+                if !is_less(&*tmp, &*v.add(i)) {
+                    break;
+                }
+                ptr::copy_nonoverlapping(v.add(i), v.add(i + 1), 1);
+                hole.dest = v.add(i);
+            }
+        }
+    }
+}
+
+fn insertion_sort<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    for i in 1..v.len() {
+        shift_tail(&mut v[..i + 1], is_less);
+    }
+}
+
+/// Merges the sorted runs `v[..mid]` and `v[mid..]`, using `buf` as scratch space for the
+/// shorter run.
+///
+/// # Safety
+/// `buf` must be valid for `min(mid, v.len() - mid)` elements and not overlap `v`.
+unsafe fn merge<T, F>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    /// Holds the part of the shorter run that wasn't merged yet. If the comparator panics, it
+    /// is copied into the gap left in `v`, so every element ends up in the slice exactly once.
+    struct MergeHole<T> {
+        start: *mut T,
+        end: *mut T,
+        dest: *mut T,
+    }
+
+    impl<T> Drop for MergeHole<T> {
+        fn drop(&mut self) {
+            unsafe {
+                let len = self.end.offset_from(self.start) as usize;
+                ptr::copy_nonoverlapping(self.start, self.dest, len);
+            }
+        }
+    }
+
+    unsafe fn get_and_increment<T>(ptr: &mut *mut T) -> *mut T {
+        let old = *ptr;
+        *ptr = unsafe { ptr.add(1) };
+        old
+    }
+
+    unsafe fn decrement_and_get<T>(ptr: &mut *mut T) -> *mut T {
+        *ptr = unsafe { ptr.sub(1) };
+        *ptr
+    }
+
+    let len = v.len();
+    let v = v.as_mut_ptr();
+    let (v_mid, v_end) = unsafe { (v.add(mid), v.add(len)) };
+
+    let mut hole;
+    if mid <= len - mid {
+        // the left run is shorter, so move it out and merge forwards
+        unsafe {
+            ptr::copy_nonoverlapping(v, buf, mid);
+            hole = MergeHole {
+                start: buf,
+                end: buf.add(mid),
+                dest: v,
+            };
+        }
+
+        let left = &mut hole.start;
+        let mut right = v_mid;
+        let out = &mut hole.dest;
+
+        while *left < hole.end && right < v_end {
+            // taking from the left on ties keeps the merge stable
+            unsafe {
+                let to_copy = if is_less(&*right, &**left) {
+                    get_and_increment(&mut right)
+                } else {
+                    get_and_increment(left)
+                };
+                ptr::copy_nonoverlapping(to_copy, get_and_increment(out), 1);
+            }
+        }
+    } else {
+        // the right run is shorter, so move it out and merge backwards
+        unsafe {
+            ptr::copy_nonoverlapping(v_mid, buf, len - mid);
+            hole = MergeHole {
+                start: buf,
+                end: buf.add(len - mid),
+                dest: v_mid,
+            };
+        }
+
+        let left = &mut hole.dest;
+        let right = &mut hole.end;
+        let mut out = v_end;
+
+        while v < *left && buf < *right {
+            // taking from the right on ties keeps the merge stable
+            unsafe {
+                let to_copy = if is_less(&*right.sub(1), &*left.sub(1)) {
+                    decrement_and_get(left)
+                } else {
+                    decrement_and_get(right)
+                };
+                ptr::copy_nonoverlapping(to_copy, decrement_and_get(&mut out), 1);
+            }
+        }
+    }
+    // whatever is left of the shorter run goes into the gap when hole is dropped
+}
+
+#[derive(Clone, Copy)]
+struct Run {
+    start: usize,
+    len: usize,
+}
+
+/// Stable, timsort-style merge sort. Finds natural runs from the back, extends short ones
+/// with insertion sort, and merges them while keeping the run lengths balanced.
+pub(super) fn merge_sort<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    const MAX_INSERTION: usize = 20;
+    const MIN_RUN: usize = 10;
+
+    // zero sized types have nothing to sort
+    if mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    let len = v.len();
+    if len <= MAX_INSERTION {
+        if len >= 2 {
+            for i in (0..len - 1).rev() {
+                shift_head(&mut v[i..], is_less);
+            }
+        }
+        return;
+    }
+
+    // merges never need more scratch space than the shorter run
+    let buf = RawVec::<T>::with_capacity(len / 2);
+    let mut runs: Vec<Run> = Vec::new();
+    let mut end = len;
+    while end > 0 {
+        let mut start = end - 1;
+        if start > 0 {
+            start -= 1;
+            unsafe {
+                if is_less(v.get_unchecked(start + 1), v.get_unchecked(start)) {
+                    // only strictly descending runs are reversed, so equal elements keep order
+                    while start > 0 && is_less(v.get_unchecked(start), v.get_unchecked(start - 1)) {
+                        start -= 1;
+                    }
+                    v[start..end].reverse();
+                } else {
+                    while start > 0 && !is_less(v.get_unchecked(start), v.get_unchecked(start - 1))
+                    {
+                        start -= 1;
+                    }
+                }
+            }
+        }
+
+        while start > 0 && end - start < MIN_RUN {
+            start -= 1;
+            shift_head(&mut v[start..end], is_less);
+        }
+
+        runs.push(Run {
+            start,
+            len: end - start,
+        });
+        end = start;
+
+        while let Some(r) = collapse(&runs) {
+            let left = runs[r + 1];
+            let right = runs[r];
+            unsafe {
+                merge(
+                    &mut v[left.start..right.start + right.len],
+                    left.len,
+                    buf.ptr(),
+                    is_less,
+                );
+            }
+            runs[r] = Run {
+                start: left.start,
+                len: left.len + right.len,
+            };
+            runs.remove(r + 1);
+        }
+    }
+
+    debug_assert!(runs.len() == 1 && runs[0].start == 0 && runs[0].len == len);
+}
+
+/// Returns the index of the run pair to merge next, keeping the timsort invariants:
+/// every run is longer than the one after it, and longer than the two after it combined.
+fn collapse(runs: &[Run]) -> Option<usize> {
+    let n = runs.len();
+    if n >= 2
+        && (runs[n - 1].start == 0
+            || runs[n - 2].len <= runs[n - 1].len
+            || (n >= 3 && runs[n - 3].len <= runs[n - 2].len + runs[n - 1].len)
+            || (n >= 4 && runs[n - 4].len <= runs[n - 3].len + runs[n - 2].len))
+    {
+        if n >= 3 && runs[n - 3].len < runs[n - 1].len {
+            Some(n - 3)
+        } else {
+            Some(n - 2)
+        }
+    } else {
+        None
+    }
+}
+
+/// Sorts `v` with an O(n log n) worst case, used when quicksort keeps picking bad pivots.
+fn heapsort<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    let mut sift_down = |v: &mut [T], mut node: usize| loop {
+        let mut child = 2 * node + 1;
+        if child >= v.len() {
+            break;
+        }
+        if child + 1 < v.len() && is_less(&v[child], &v[child + 1]) {
+            child += 1;
+        }
+        if !is_less(&v[node], &v[child]) {
+            break;
+        }
+        v.swap(node, child);
+        node = child;
+    };
+
+    for i in (0..v.len() / 2).rev() {
+        sift_down(v, i);
+    }
+    for i in (1..v.len()).rev() {
+        v.swap(0, i);
+        sift_down(&mut v[..i], 0);
+    }
+}
+
+/// Partially sorts `v` by shifting a few out-of-order elements, and returns `true` if that
+/// was enough to sort it.
+fn partial_insertion_sort<T, F>(v: &mut [T], is_less: &mut F) -> bool
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    const MAX_STEPS: usize = 5;
+    const SHORTEST_SHIFTING: usize = 50;
+
+    let len = v.len();
+    let mut i = 1;
+
+    for _ in 0..MAX_STEPS {
+        unsafe {
+            while i < len && !is_less(v.get_unchecked(i), v.get_unchecked(i - 1)) {
+                i += 1;
+            }
+        }
+
+        if i == len {
+            return true;
+        }
+        // shifting elements of short slices isn't worth it
+        if len < SHORTEST_SHIFTING {
+            return false;
+        }
+
+        v.swap(i - 1, i);
+        shift_tail(&mut v[..i], is_less);
+        shift_head(&mut v[i..], is_less);
+    }
+
+    false
+}
+
+/// Reads the pivot out of `v[0]` for the duration of `f`, writing it back even if `f` panics.
+fn with_pivot<T, R>(v: &mut [T], f: impl FnOnce(&mut [T], &T) -> R) -> R {
+    let (pivot, rest) = v.split_at_mut(1);
+    let pivot = &mut pivot[0];
+    // comparing against a copy on the stack keeps the pivot out of the slice being shuffled
+    let tmp = ManuallyDrop::new(unsafe { ptr::read(pivot) });
+    let _pivot_guard = CopyOnDrop {
+        src: &*tmp,
+        dest: pivot,
+    };
+    f(rest, &tmp)
+}
+
+/// Partitions `v` into elements smaller than `v[pivot]`, followed by elements greater than or
+/// equal to it. Returns the new index of the pivot, and whether `v` was already partitioned.
+fn partition<T, F>(v: &mut [T], pivot: usize, is_less: &mut F) -> (usize, bool)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    v.swap(0, pivot);
+    let (mid, was_partitioned) = with_pivot(v, |v, pivot| {
+        let mut l = 0;
+        let mut r = v.len();
+        unsafe {
+            while l < r && is_less(v.get_unchecked(l), pivot) {
+                l += 1;
+            }
+            while l < r && !is_less(v.get_unchecked(r - 1), pivot) {
+                r -= 1;
+            }
+        }
+        let was_partitioned = l >= r;
+
+        // Hoare partitioning: [..l] is smaller than the pivot, [r..] is not
+        let v = v.as_mut_ptr();
+        loop {
+            unsafe {
+                while l < r && is_less(&*v.add(l), pivot) {
+                    l += 1;
+                }
+                while l < r && !is_less(&*v.add(r - 1), pivot) {
+                    r -= 1;
+                }
+                if l >= r {
+                    break;
+                }
+                r -= 1;
+                // a comparator that contradicts itself can make both scans stop at the same
+                // element, which must not be swapped with itself
+                if l == r {
+                    break;
+                }
+                ptr::swap_nonoverlapping(v.add(l), v.add(r), 1);
+                l += 1;
+            }
+        }
+        (l, was_partitioned)
+    });
+
+    // move the pivot between the two partitions
+    v.swap(0, mid);
+    (mid, was_partitioned)
+}
+
+/// Partitions `v` into elements equal to `v[pivot]` followed by elements greater than it,
+/// assuming nothing is smaller. Returns the number of elements equal to the pivot.
+fn partition_equal<T, F>(v: &mut [T], pivot: usize, is_less: &mut F) -> usize
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    v.swap(0, pivot);
+    let l = with_pivot(v, |v, pivot| {
+        let mut l = 0;
+        let mut r = v.len();
+        let v = v.as_mut_ptr();
+        loop {
+            unsafe {
+                while l < r && !is_less(pivot, &*v.add(l)) {
+                    l += 1;
+                }
+                while l < r && is_less(pivot, &*v.add(r - 1)) {
+                    r -= 1;
+                }
+                if l >= r {
+                    break;
+                }
+                r -= 1;
+                // a comparator that contradicts itself can make both scans stop at the same
+                // element, which must not be swapped with itself
+                if l == r {
+                    break;
+                }
+                ptr::swap_nonoverlapping(v.add(l), v.add(r), 1);
+                l += 1;
+            }
+        }
+        l
+    });
+
+    // the pivot itself is equal too
+    l + 1
+}
+
+/// Swaps a few elements into pseudo-random positions, to break up patterns that keep
+/// producing unbalanced partitions.
+fn break_patterns<T>(v: &mut [T]) {
+    let len = v.len();
+    if len >= 8 {
+        // xorshift, seeded with the length so that sorting stays deterministic
+        let mut random = len as u32;
+        let mut gen_u32 = || {
+            random ^= random << 13;
+            random ^= random >> 17;
+            random ^= random << 5;
+            random
+        };
+        let mut gen_usize = || {
+            if usize::BITS <= 32 {
+                gen_u32() as usize
+            } else {
+                (((gen_u32() as u64) << 32) | (gen_u32() as u64)) as usize
+            }
+        };
+
+        let modulus = len.next_power_of_two();
+        let pos = len / 4 * 2;
+        for i in 0..3 {
+            let mut other = gen_usize() & (modulus - 1);
+            if other >= len {
+                other -= len;
+            }
+            v.swap(pos - 1 + i, other);
+        }
+    }
+}
+
+/// Picks a pivot with a median of three, or a median of medians for longer slices. Also
+/// returns `true` if the slice looks already sorted, and reverses it if it looks descending.
+fn choose_pivot<T, F>(v: &mut [T], is_less: &mut F) -> (usize, bool)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    const SHORTEST_MEDIAN_OF_MEDIANS: usize = 50;
+    const MAX_SWAPS: usize = 4 * 3;
+
+    let len = v.len();
+    let mut a = len / 4;
+    let mut b = len / 4 * 2;
+    let mut c = len / 4 * 3;
+    let mut swaps = 0;
+
+    if len >= 8 {
+        let mut sort2 = |a: &mut usize, b: &mut usize| unsafe {
+            if is_less(v.get_unchecked(*b), v.get_unchecked(*a)) {
+                mem::swap(a, b);
+                swaps += 1;
+            }
+        };
+        let mut sort3 = |a: &mut usize, b: &mut usize, c: &mut usize| {
+            sort2(a, b);
+            sort2(b, c);
+            sort2(a, b);
+        };
+
+        if len >= SHORTEST_MEDIAN_OF_MEDIANS {
+            let mut sort_adjacent = |a: &mut usize| {
+                let tmp = *a;
+                sort3(&mut (tmp - 1), a, &mut (tmp + 1));
+            };
+            sort_adjacent(&mut a);
+            sort_adjacent(&mut b);
+            sort_adjacent(&mut c);
+        }
+
+        sort3(&mut a, &mut b, &mut c);
+    }
+
+    if swaps < MAX_SWAPS {
+        (b, swaps == 0)
+    } else {
+        // the maximum number of swaps means the slice is most likely descending
+        v.reverse();
+        (len - 1 - b, true)
+    }
+}
+
+/// Sorts `v` recursively. `pred` is the pivot of the parent partition, which is known to be
+/// smaller than or equal to every element of `v`. Falls back to heapsort once `limit`
+/// unbalanced partitions have been made.
+fn recurse<'a, T, F>(mut v: &'a mut [T], is_less: &mut F, mut pred: Option<&'a T>, mut limit: u32)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    const MAX_INSERTION: usize = 20;
+
+    let mut was_balanced = true;
+    let mut was_partitioned = true;
+
+    loop {
+        let len = v.len();
+
+        if len <= MAX_INSERTION {
+            insertion_sort(v, is_less);
+            return;
+        }
+
+        if limit == 0 {
+            heapsort(v, is_less);
+            return;
+        }
+
+        if !was_balanced {
+            break_patterns(v);
+            limit -= 1;
+        }
+
+        let (pivot, likely_sorted) = choose_pivot(v, is_less);
+
+        if was_balanced && was_partitioned && likely_sorted && partial_insertion_sort(v, is_less) {
+            return;
+        }
+
+        // if the pivot equals the predecessor, it's the smallest element in the slice, so
+        // the elements equal to it can be split off and skipped
+        if let Some(p) = pred {
+            if !is_less(p, &v[pivot]) {
+                let mid = partition_equal(v, pivot, is_less);
+                v = &mut v[mid..];
+                continue;
+            }
+        }
+
+        let (mid, was_p) = partition(v, pivot, is_less);
+        was_balanced = cmp::min(mid, len - mid) >= len / 8;
+        was_partitioned = was_p;
+
+        let (left, right) = v.split_at_mut(mid);
+        let (pivot, right) = right.split_at_mut(1);
+        let pivot = &pivot[0];
+
+        // recurse into the shorter side only, to bound the stack depth
+        if left.len() < right.len() {
+            recurse(left, is_less, pred, limit);
+            v = right;
+            pred = Some(pivot);
+        } else {
+            recurse(right, is_less, Some(pivot), limit);
+            v = left;
+        }
+    }
+}
+
+/// Unstable pattern-defeating quicksort, O(n log n) in the worst case.
+pub(super) fn quicksort<T, F>(v: &mut [T], is_less: &mut F)
+where
+    F: FnMut(&T, &T) -> bool,
+{
+    if mem::size_of::<T>() == 0 {
+        return;
+    }
+
+    let limit = usize::BITS - v.len().leading_zeros();
+    recurse(v, is_less, None, limit);
+}
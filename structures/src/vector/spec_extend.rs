@@ -0,0 +1,127 @@
+use std::alloc::Allocator;
+use std::iter::TrustedLen;
+use std::ops::Range;
+use std::ptr;
+
+use super::growth::GrowthPolicy;
+use super::iter::IntoIter;
+use super::vec::Vec;
+
+/// Picks the fastest way to extend a `Vec` from a given iterator type.
+pub(super) trait SpecExtend<T, I> {
+    fn spec_extend(&mut self, iter: I);
+}
+
+impl<T, I, A: Allocator, G: GrowthPolicy> SpecExtend<T, I> for Vec<T, A, G>
+where
+    I: Iterator<Item = T>,
+{
+    default fn spec_extend(&mut self, iter: I) {
+        self.extend_desugared(iter)
+    }
+}
+
+impl<T, I, A: Allocator, G: GrowthPolicy> SpecExtend<T, I> for Vec<T, A, G>
+where
+    I: TrustedLen<Item = T>,
+{
+    default fn spec_extend(&mut self, iter: I) {
+        self.extend_trusted(iter)
+    }
+}
+
+impl<T, A1: Allocator, A2: Allocator, G: GrowthPolicy> SpecExtend<T, IntoIter<T, A2>>
+    for Vec<T, A1, G>
+{
+    fn spec_extend(&mut self, mut iter: IntoIter<T, A2>) {
+        // SAFETY: the elements are moved out with a single copy, and forgotten by the iterator
+        unsafe {
+            self.append_elements(iter.as_slice());
+        }
+        iter.forget_remaining_elements();
+    }
+}
+
+impl<'a, T: Clone + 'a, I, A: Allocator, G: GrowthPolicy> SpecExtend<&'a T, I> for Vec<T, A, G>
+where
+    I: Iterator<Item = &'a T>,
+{
+    fn spec_extend(&mut self, iter: I) {
+        self.spec_extend(iter.cloned())
+    }
+}
+
+/// Writes the length back when dropped, so that a panic mid-extend keeps the written elements.
+struct SetLenOnDrop<'a, T, A: Allocator, G: GrowthPolicy> {
+    vec: &'a mut Vec<T, A, G>,
+    local_len: usize,
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for SetLenOnDrop<'_, T, A, G> {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe { self.vec.set_len(self.local_len) }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Extends from an iterator whose `size_hint` is exact, reserving once and skipping the
+    /// capacity check for every element.
+    pub(super) fn extend_trusted(&mut self, iter: impl TrustedLen<Item = T>) {
+        let (low, high) = iter.size_hint();
+        let Some(additional) = high else {
+            // more than usize::MAX elements can never fit
+            panic!("capacity overflow");
+        };
+        debug_assert_eq!(low, additional);
+        self.reserve(additional);
+
+        let ptr = self.as_mut_ptr();
+        let local_len = self.len();
+        let mut guard = SetLenOnDrop {
+            vec: self,
+            local_len,
+        };
+        iter.for_each(|element| unsafe {
+            ptr::write(ptr.add(guard.local_len), element);
+            guard.local_len += 1;
+        });
+    }
+
+    /// Moves the elements behind `other` to the end of the vector.
+    ///
+    /// # Safety
+    /// The elements must not be used again by the caller, unless `T` is `Copy`.
+    pub(super) unsafe fn append_elements(&mut self, other: *const [T]) {
+        let count = other.len();
+        self.reserve(count);
+        let len = self.len();
+        unsafe {
+            ptr::copy_nonoverlapping(other as *const T, self.as_mut_ptr().add(len), count);
+            self.set_len(len + count);
+        }
+    }
+
+    /// Appends clones of the elements in `src`.
+    ///
+    /// # Safety
+    /// `src` must be within `0..len`, and there must be room for `src.len()` more elements.
+    pub(super) unsafe fn clone_from_within(&mut self, src: Range<usize>)
+    where
+        T: Clone,
+    {
+        let ptr = self.as_mut_ptr();
+        let local_len = self.len();
+        let mut guard = SetLenOnDrop {
+            vec: self,
+            local_len,
+        };
+        for i in src {
+            unsafe {
+                let element = (*ptr.add(i)).clone();
+                ptr::write(ptr.add(guard.local_len), element);
+            }
+            guard.local_len += 1;
+        }
+    }
+}
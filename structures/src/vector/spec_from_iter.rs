@@ -0,0 +1,80 @@
+use std::alloc::Global;
+use std::iter::TrustedLen;
+use std::mem::ManuallyDrop;
+use std::{cmp, ptr};
+
+use super::growth::GrowthPolicy;
+use super::iter::IntoIter;
+use super::rawvec::RawVec;
+use super::spec_extend::SpecExtend;
+use super::vec::Vec;
+
+/// Picks the fastest way to collect a given iterator type into a `Vec`.
+pub(super) trait SpecFromIter<T, I> {
+    fn from_iter(iter: I) -> Self;
+}
+
+impl<T, I, G: GrowthPolicy> SpecFromIter<T, I> for Vec<T, Global, G>
+where
+    I: Iterator<Item = T>,
+{
+    default fn from_iter(mut iterator: I) -> Self {
+        // Light optimization taken from SpecFromIterNested::from_iter.
+        // Determines initial capacity using a size hint.
+        let mut vector = match iterator.next() {
+            None => return Vec::new(),
+            Some(element) => {
+                let (lower, _) = iterator.size_hint();
+                let initial_capacity =
+                    cmp::max(RawVec::<T, Global, G>::min_non_zero_cap(), lower.saturating_add(1));
+                let mut vector = Vec::with_capacity(initial_capacity);
+                unsafe {
+                    ptr::write(vector.as_mut_ptr(), element);
+                    vector.set_len(1);
+                }
+                vector
+            }
+        };
+
+        vector.spec_extend(iterator);
+        vector
+    }
+}
+
+impl<T, I, G: GrowthPolicy> SpecFromIter<T, I> for Vec<T, Global, G>
+where
+    I: TrustedLen<Item = T>,
+{
+    default fn from_iter(iterator: I) -> Self {
+        let mut vector = match iterator.size_hint() {
+            (_, Some(upper)) => Vec::with_capacity(upper),
+            _ => panic!("capacity overflow"),
+        };
+        vector.extend_trusted(iterator);
+        vector
+    }
+}
+
+impl<T, G: GrowthPolicy> SpecFromIter<T, IntoIter<T>> for Vec<T, Global, G> {
+    fn from_iter(iterator: IntoIter<T>) -> Self {
+        // reuse the allocation if nothing was taken out yet, or if the rest still fills at least
+        // half of it, so that collecting doesn't hold on to a mostly empty buffer
+        let has_advanced = !ptr::eq(iterator.buf.as_ptr(), iterator.ptr);
+        if !has_advanced || iterator.len() >= iterator.cap / 2 {
+            let it = ManuallyDrop::new(iterator);
+            let len = it.len();
+            // SAFETY: the remaining elements are moved to the front of the buffer they already
+            // live in, and the iterator is never dropped
+            unsafe {
+                if has_advanced {
+                    ptr::copy(it.ptr, it.buf.as_ptr(), len);
+                }
+                return Vec::from_raw_parts(it.buf.as_ptr(), len, it.cap);
+            }
+        }
+
+        let mut vector = Vec::new();
+        vector.spec_extend(iterator);
+        vector
+    }
+}
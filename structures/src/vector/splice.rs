@@ -0,0 +1,86 @@
+use std::alloc::{Allocator, Global};
+use std::fmt::{self, Debug};
+
+use super::drain::Drain;
+use super::growth::{Doubling, GrowthPolicy};
+use super::vec::Vec;
+
+pub struct Splice<'a, I: Iterator + 'a, A: Allocator + 'a = Global, G: GrowthPolicy = Doubling> {
+    pub(super) drain: Drain<'a, I::Item, A, G>,
+    pub(super) replace_with: I,
+}
+
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> Iterator for Splice<'_, I, A, G> {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.drain.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> DoubleEndedIterator for Splice<'_, I, A, G> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.drain.next_back()
+    }
+}
+
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> ExactSizeIterator for Splice<'_, I, A, G> {}
+
+impl<I, A: Allocator, G: GrowthPolicy> Debug for Splice<'_, I, A, G>
+where
+    I: Iterator + Debug,
+    I::Item: Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Splice")
+            .field("drain", &self.drain)
+            .field("replace_with", &self.replace_with)
+            .finish()
+    }
+}
+
+impl<I: Iterator, A: Allocator, G: GrowthPolicy> Drop for Splice<'_, I, A, G> {
+    fn drop(&mut self) {
+        // drop the rest of the removed range, then leave nothing for Drain::drop
+        self.drain.by_ref().for_each(drop);
+        self.drain.iter = [].iter();
+
+        unsafe {
+            // no tail to move, so simply append the replacement
+            if self.drain.tail_len == 0 {
+                self.drain.vec.as_mut().extend(self.replace_with.by_ref());
+                return;
+            }
+
+            // first, fill the gap left by the removed range
+            if !self.drain.fill(&mut self.replace_with) {
+                return;
+            }
+
+            // then use the lower size hint to make room for more elements
+            let (lower, _) = self.replace_with.size_hint();
+            if lower > 0 {
+                self.drain.move_tail(lower);
+                if !self.drain.fill(&mut self.replace_with) {
+                    return;
+                }
+            }
+
+            // collect whatever remains, so that the tail is moved exactly once more
+            let mut collected = self.replace_with.by_ref().collect::<Vec<I::Item>>();
+            if !collected.is_empty() {
+                self.drain.move_tail(collected.len());
+                let filled = self.drain.fill(&mut collected.drain(..));
+                debug_assert!(filled);
+            }
+        }
+        // Drain::drop then moves the tail back next to the inserted elements
+    }
+}
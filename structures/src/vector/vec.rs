@@ -1,19 +1,31 @@
+use std::alloc::{Allocator, Global};
+use std::borrow::{Borrow, BorrowMut};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::mem::{self, ManuallyDrop};
-use std::ops::{Deref, DerefMut, Index, IndexMut};
+use std::ops::{Deref, DerefMut, Index, IndexMut, Range, RangeBounds};
 use std::ptr::NonNull;
 use std::slice::SliceIndex;
-use std::{cmp, ptr, slice};
+use std::{cmp, iter, ptr, slice};
 
-use super::iter::IntoIter;
-use super::rawvec::{RawVec, TryReserveError};
+use super::drain::Drain;
+use super::extract_if::ExtractIf;
+use super::growth::{Doubling, GrowthPolicy};
+use super::iter::{IntoIter, Iter, IterMut};
+use super::rawvec::{RawPartsError, RawVec, TryReserveError};
+use super::sort;
+use super::spec_extend::SpecExtend;
+use super::spec_from_iter::SpecFromIter;
+use super::splice::Splice;
 
-pub struct Vec<T> {
-    buf: RawVec<T>,
+pub struct Vec<T, A: Allocator = Global, G: GrowthPolicy = Doubling> {
+    pub(super) buf: RawVec<T, A, G>,
     len: usize,
 }
 
-impl<T> Vec<T> {
+impl<T, G: GrowthPolicy> Vec<T, Global, G> {
     #[inline]
     pub const fn new() -> Self {
         Self {
@@ -24,33 +36,143 @@ impl<T> Vec<T> {
 
     #[inline]
     pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+
+    #[inline]
+    pub fn with_capacity_zeroed(capacity: usize) -> Self {
+        Self::with_capacity_zeroed_in(capacity, Global)
+    }
+
+    #[inline]
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, TryReserveError> {
+        Self::try_with_capacity_in(capacity, Global)
+    }
+
+    /// Collects an iterator like `collect`, but reports allocation failure instead of aborting.
+    pub fn try_from_iter<I: IntoIterator<Item = T>>(iter: I) -> Result<Self, TryReserveError> {
+        let mut v = Vec::new();
+        v.try_extend(iter)?;
+        Ok(v)
+    }
+
+    /// # Safety
+    /// See [`Vec::from_raw_parts_in`].
+    #[inline]
+    pub unsafe fn from_raw_parts(buf: *mut T, len: usize, capacity: usize) -> Self {
+        unsafe { Self::from_raw_parts_in(buf, len, capacity, Global) }
+    }
+
+    #[inline]
+    pub fn into_raw_parts(self) -> (*mut T, usize, usize) {
+        let mut m = ManuallyDrop::new(self);
+        (m.as_mut_ptr(), m.len(), m.capacity())
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    #[inline]
+    pub const fn new_in(alloc: A) -> Self {
         Self {
-            buf: RawVec::with_capacity(capacity),
+            buf: RawVec::new_in(alloc),
             len: 0,
         }
     }
 
     #[inline]
-    pub fn with_capacity_zeroed(capacity: usize) -> Self {
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
         Self {
-            buf: RawVec::with_capacity_zeroed(capacity),
+            buf: RawVec::with_capacity_in(capacity, alloc),
             len: 0,
         }
     }
 
     #[inline]
-    pub fn from_raw_parts(buf: *mut T, len: usize, capacity: usize) -> Vec<T> {
-        Vec {
-            buf: RawVec::from_raw_parts(buf, capacity),
+    pub fn with_capacity_zeroed_in(capacity: usize, alloc: A) -> Self {
+        Self {
+            buf: RawVec::with_capacity_zeroed_in(capacity, alloc),
+            len: 0,
+        }
+    }
+
+    #[inline]
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, TryReserveError> {
+        Ok(Self {
+            buf: RawVec::try_with_capacity_in(capacity, alloc)?,
+            len: 0,
+        })
+    }
+
+    /// Rebuilds a `Vec` from its raw parts. Debug builds check the parts with
+    /// [`Vec::check_raw_parts`] and panic if they are invalid.
+    ///
+    /// # Safety
+    /// `buf` must have been allocated by `alloc` with the layout of `capacity` elements of `T`,
+    /// as by [`RawVec::from_raw_parts_in`], and the first `len` elements must be initialized.
+    #[inline]
+    pub unsafe fn from_raw_parts_in(
+        buf: *mut T,
+        len: usize,
+        capacity: usize,
+        alloc: A,
+    ) -> Self {
+        if cfg!(debug_assertions) {
+            if let Err(err) = Self::check_raw_parts(buf, len, capacity) {
+                panic!("invalid raw parts: {err}");
+            }
+        }
+        Self {
+            buf: unsafe { RawVec::from_raw_parts_in(buf, capacity, alloc) },
             len,
         }
     }
 
+    /// Checks that `len` fits within `capacity`, along with the checks of [`RawVec::check_raw_parts`].
+    pub fn check_raw_parts(
+        buf: *const T,
+        len: usize,
+        capacity: usize,
+    ) -> Result<(), RawPartsError> {
+        if len > capacity {
+            return Err(RawPartsError::LengthExceedsCapacity { len, capacity });
+        }
+        RawVec::<T, A>::check_raw_parts(buf, capacity)
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    /// Switches to another growth policy, keeping the same allocation and elements.
+    pub fn into_policy<H: GrowthPolicy>(self) -> Vec<T, A, H> {
+        let m = ManuallyDrop::new(self);
+        Vec {
+            // SAFETY: the buffer is moved out, and m is never dropped
+            buf: unsafe { ptr::read(&m.buf) }.into_policy(),
+            len: m.len,
+        }
+    }
+
+    #[inline]
+    pub fn into_raw_parts_with_alloc(self) -> (*mut T, usize, usize, A) {
+        let mut m = ManuallyDrop::new(self);
+        let alloc = unsafe { ptr::read(m.allocator()) };
+        (m.as_mut_ptr(), m.len(), m.capacity(), alloc)
+    }
+
+    #[inline]
+    pub fn allocator(&self) -> &A {
+        self.buf.allocator()
+    }
+
     #[inline]
     pub fn len(&self) -> usize {
         self.len
     }
 
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     #[inline]
     pub fn capacity(&self) -> usize {
         self.buf.capacity()
@@ -66,9 +188,11 @@ impl<T> Vec<T> {
         self.buf.ptr()
     }
 
+    /// # Safety
+    /// `len` must not exceed the capacity, and the first `len` elements must be initialized.
     #[inline]
-    pub(crate) unsafe fn set_len(&mut self, len: usize) {
-        assert!(len <= self.capacity());
+    pub unsafe fn set_len(&mut self, len: usize) {
+        debug_assert!(len <= self.capacity());
         self.len = len;
     }
 
@@ -88,6 +212,22 @@ impl<T> Vec<T> {
         }
     }
 
+    /// Appends an element, or hands it back along with the error if the vector can't grow.
+    pub fn try_push(&mut self, value: T) -> Result<(), (T, TryReserveError)> {
+        if self.len == self.capacity() {
+            if let Err(err) = self.buf.try_reserve(self.len, 1) {
+                return Err((value, err));
+            }
+        }
+
+        // SAFETY: len < capacity after the reserve above
+        unsafe {
+            ptr::write(self.as_mut_ptr().add(self.len), value);
+            self.len += 1;
+        }
+        Ok(())
+    }
+
     pub fn pop(&mut self) -> Option<T> {
         if self.len == 0 {
             return None;
@@ -128,6 +268,33 @@ impl<T> Vec<T> {
         }
     }
 
+    /// Inserts an element at `index`, or hands it back along with the error if the vector
+    /// can't grow.
+    ///
+    /// # Panics
+    /// Panics if `index > len`.
+    pub fn try_insert(&mut self, index: usize, element: T) -> Result<(), (T, TryReserveError)> {
+        let len = self.len();
+        assert!(index <= len, "index out of bounds");
+
+        if len == self.capacity() {
+            if let Err(err) = self.buf.try_reserve(len, 1) {
+                return Err((element, err));
+            }
+        }
+
+        // SAFETY: index <= len < capacity after the reserve above
+        unsafe {
+            let p = self.as_mut_ptr().add(index);
+            if index < len {
+                ptr::copy(p, p.add(1), len - index);
+            }
+            ptr::write(p, element);
+            self.set_len(len + 1);
+        }
+        Ok(())
+    }
+
     pub fn remove(&mut self, index: usize) -> T {
         // check bounds
         let len = self.len();
@@ -162,12 +329,6 @@ impl<T> Vec<T> {
         }
     }
 
-    #[inline]
-    pub fn into_raw_parts(self) -> (*mut T, usize, usize) {
-        let mut m = ManuallyDrop::new(self);
-        (m.as_mut_ptr(), m.len(), m.capacity())
-    }
-
     pub fn reserve(&mut self, additional: usize) {
         self.buf.reserve(self.len, additional);
     }
@@ -184,6 +345,70 @@ impl<T> Vec<T> {
         self.buf.try_reserve_exact(self.len, additional)
     }
 
+    pub fn shrink_to_fit(&mut self) {
+        if self.capacity() > self.len {
+            self.buf.shrink_to(self.len);
+        }
+    }
+
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        if self.capacity() > min_capacity {
+            self.buf.shrink_to(cmp::max(self.len, min_capacity));
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+
+        // shorten first, so that a panicking drop can't cause the elements to be dropped twice
+        // SAFETY: the elements in len..self.len are initialized and dropped exactly once
+        unsafe {
+            let remaining = self.len - len;
+            let tail = ptr::slice_from_raw_parts_mut(self.as_mut_ptr().add(len), remaining);
+            self.len = len;
+            ptr::drop_in_place(tail);
+        }
+    }
+
+    pub fn resize_with<F: FnMut() -> T>(&mut self, new_len: usize, f: F) {
+        let len = self.len;
+        if new_len > len {
+            self.extend(iter::repeat_with(f).take(new_len - len));
+        } else {
+            self.truncate(new_len);
+        }
+    }
+
+    pub fn split_off(&mut self, at: usize) -> Self
+    where
+        A: Clone,
+    {
+        assert!(at <= self.len, "`at` split index out of bounds");
+
+        let other_len = self.len - at;
+        let mut other = Self::with_capacity_in(other_len, self.allocator().clone());
+
+        // SAFETY: the tail is moved into other, which has room for other_len elements
+        unsafe {
+            self.len = at;
+            ptr::copy_nonoverlapping(self.as_ptr().add(at), other.as_mut_ptr(), other_len);
+            other.set_len(other_len);
+        }
+        other
+    }
+
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self)
+    }
+
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self)
+    }
+
     #[inline]
     pub fn as_slice(&self) -> &[T] {
         self
@@ -203,7 +428,381 @@ impl<T> Vec<T> {
         }
     }
 
-    pub fn extend<I: Iterator<Item = T>>(&mut self, mut iterator: I) {
+    pub fn drain<R>(&mut self, range: R) -> Drain<'_, T, A, G>
+    where
+        R: RangeBounds<usize>,
+    {
+        let len = self.len();
+        let Range { start, end } = slice::range(range, ..len);
+
+        // shorten the vector first, so that leaking the Drain only leaks the
+        // drained range and the tail, and never exposes moved-out elements
+        unsafe {
+            self.set_len(start);
+            let range_slice = slice::from_raw_parts(self.as_ptr().add(start), end - start);
+            Drain {
+                tail_start: end,
+                tail_len: len - end,
+                iter: range_slice.iter(),
+                vec: NonNull::from(self),
+            }
+        }
+    }
+
+    #[inline]
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Splice<'_, I::IntoIter, A, G>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        Splice {
+            drain: self.drain(range),
+            replace_with: replace_with.into_iter(),
+        }
+    }
+
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, T, F, A, G>
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let old_len = self.len();
+
+        // elements are moved around while extracting, so hide them until ExtractIf is dropped
+        unsafe { self.set_len(0) };
+        ExtractIf {
+            vec: self,
+            idx: 0,
+            del: 0,
+            old_len,
+            pred,
+        }
+    }
+
+    /// Converts into a boxed slice, shrinking the allocation to fit the elements first.
+    pub fn into_boxed_slice(mut self) -> Box<[T], A> {
+        self.shrink_to_fit();
+        let (ptr, len, _, alloc) = self.into_raw_parts_with_alloc();
+        unsafe { Box::from_raw_in(ptr::slice_from_raw_parts_mut(ptr, len), alloc) }
+    }
+
+    /// Consumes the vector without freeing its allocation, so the elements live on forever.
+    pub fn leak<'a>(self) -> &'a mut [T]
+    where
+        A: 'a,
+    {
+        let mut m = ManuallyDrop::new(self);
+        unsafe { slice::from_raw_parts_mut(m.as_mut_ptr(), m.len) }
+    }
+
+    /// Extends the vector like `extend`, but stops at the first allocation failure. The
+    /// elements appended before that are kept. Room is reserved before each element is taken
+    /// out of the iterator, so the element that didn't fit is still in it, and passing
+    /// `iter.by_ref()` keeps the rest for the caller.
+    pub fn try_extend<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), TryReserveError> {
+        let mut iterator = iter.into_iter();
+        let (lower, _) = iterator.size_hint();
+        self.try_reserve(lower)?;
+
+        loop {
+            let len = self.len;
+            if len == self.capacity() {
+                let (lower, upper) = iterator.size_hint();
+                if upper == Some(0) {
+                    return Ok(());
+                }
+                self.try_reserve(lower.saturating_add(1))?;
+            }
+            let Some(element) = iterator.next() else {
+                return Ok(());
+            };
+            // SAFETY: len < capacity after the check above
+            unsafe {
+                ptr::write(self.as_mut_ptr().add(len), element);
+                self.set_len(len + 1);
+            }
+        }
+    }
+
+    /// Appends clones of the elements in `other`.
+    pub fn extend_from_slice(&mut self, other: &[T])
+    where
+        T: Clone,
+    {
+        self.spec_extend(other.iter())
+    }
+
+    /// Appends clones of the elements in the `src` range to the end of the vector.
+    ///
+    /// # Panics
+    /// Panics if the range is out of bounds.
+    pub fn extend_from_within<R: RangeBounds<usize>>(&mut self, src: R)
+    where
+        T: Clone,
+    {
+        let range = slice::range(src, ..self.len);
+        self.reserve(range.len());
+        // SAFETY: range is within len, and there's room for range.len() more elements
+        unsafe { self.clone_from_within(range) }
+    }
+
+    /// Sorts the vector, keeping equal elements in their original order.
+    pub fn sort(&mut self)
+    where
+        T: Ord,
+    {
+        sort::merge_sort(self, &mut T::lt);
+    }
+
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> cmp::Ordering,
+    {
+        sort::merge_sort(self, &mut |a, b| compare(a, b) == cmp::Ordering::Less);
+    }
+
+    pub fn sort_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        sort::merge_sort(self, &mut |a, b| f(a).lt(&f(b)));
+    }
+
+    /// Sorts the vector without allocating, but may reorder equal elements.
+    pub fn sort_unstable(&mut self)
+    where
+        T: Ord,
+    {
+        sort::quicksort(self, &mut T::lt);
+    }
+
+    pub fn sort_unstable_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> cmp::Ordering,
+    {
+        sort::quicksort(self, &mut |a, b| compare(a, b) == cmp::Ordering::Less);
+    }
+
+    pub fn sort_unstable_by_key<K, F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> K,
+        K: Ord,
+    {
+        sort::quicksort(self, &mut |a, b| f(a).lt(&f(b)));
+    }
+
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.binary_search_by(|p| p.cmp(x))
+    }
+
+    /// Searches the sorted vector with a comparator that returns how an element orders
+    /// against the target. Returns `Ok` with the index of a match, or `Err` with the index
+    /// where the target could be inserted.
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> cmp::Ordering,
+    {
+        let mut left = 0;
+        let mut right = self.len;
+        while left < right {
+            let mid = left + (right - left) / 2;
+            // SAFETY: left <= mid < right <= len
+            let cmp = f(unsafe { &*self.as_ptr().add(mid) });
+            match cmp {
+                cmp::Ordering::Less => left = mid + 1,
+                cmp::Ordering::Greater => right = mid,
+                cmp::Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(left)
+    }
+
+    pub fn binary_search_by_key<B, F>(&self, b: &B, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> B,
+        B: Ord,
+    {
+        self.binary_search_by(|k| f(k).cmp(b))
+    }
+
+    pub fn dedup(&mut self)
+    where
+        T: PartialEq,
+    {
+        self.dedup_by(|a, b| a == b)
+    }
+
+    pub fn dedup_by_key<K, F>(&mut self, mut key: F)
+    where
+        F: FnMut(&mut T) -> K,
+        K: PartialEq,
+    {
+        self.dedup_by(|a, b| key(a) == key(b))
+    }
+
+    /// Removes consecutive elements for which `same_bucket(element, previous)` returns `true`.
+    pub fn dedup_by<F>(&mut self, mut same_bucket: F)
+    where
+        F: FnMut(&mut T, &mut T) -> bool,
+    {
+        let len = self.len;
+        if len <= 1 {
+            return;
+        }
+
+        // [..write] are the kept elements, [write..read] are dropped or moved out, and
+        // [read..len] are not visited yet. If same_bucket or a drop panics, the unvisited
+        // elements are moved over the gap so that the vector stays valid.
+        struct FillGapOnDrop<'a, T, A: Allocator, G: GrowthPolicy> {
+            read: usize,
+            write: usize,
+            vec: &'a mut Vec<T, A, G>,
+        }
+
+        impl<T, A: Allocator, G: GrowthPolicy> Drop for FillGapOnDrop<'_, T, A, G> {
+            fn drop(&mut self) {
+                unsafe {
+                    let ptr = self.vec.as_mut_ptr();
+                    let len = self.vec.len;
+                    let items_left = len - self.read;
+                    ptr::copy(ptr.add(self.read), ptr.add(self.write), items_left);
+                    self.vec.set_len(len - (self.read - self.write));
+                }
+            }
+        }
+
+        let mut gap = FillGapOnDrop {
+            read: 1,
+            write: 1,
+            vec: self,
+        };
+        let ptr = gap.vec.as_mut_ptr();
+
+        unsafe {
+            while gap.read < len {
+                let read_ptr = ptr.add(gap.read);
+                let prev_ptr = ptr.add(gap.write - 1);
+
+                if same_bucket(&mut *read_ptr, &mut *prev_ptr) {
+                    // bump read first, so a panicking drop doesn't drop it again
+                    gap.read += 1;
+                    ptr::drop_in_place(read_ptr);
+                } else {
+                    ptr::copy(read_ptr, ptr.add(gap.write), 1);
+                    gap.write += 1;
+                    gap.read += 1;
+                }
+            }
+
+            gap.vec.set_len(gap.write);
+            mem::forget(gap);
+        }
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.retain_mut(|element| f(element));
+    }
+
+    /// Keeps only the elements for which `f` returns `true`, in their original order.
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut T) -> bool,
+    {
+        let original_len = self.len;
+        if original_len == 0 {
+            return;
+        }
+
+        // the length is 0 while elements are shuffled, so a panic can't drop anything twice
+        unsafe { self.set_len(0) };
+
+        // [..processed - deleted] are kept, [processed - deleted..processed] are holes, and
+        // [processed..original_len] are not visited yet. Shifts the rest back over the holes
+        // on the way out, whether f panicked or not.
+        struct BackshiftOnDrop<'a, T, A: Allocator, G: GrowthPolicy> {
+            v: &'a mut Vec<T, A, G>,
+            processed_len: usize,
+            deleted_cnt: usize,
+            original_len: usize,
+        }
+
+        impl<T, A: Allocator, G: GrowthPolicy> Drop for BackshiftOnDrop<'_, T, A, G> {
+            fn drop(&mut self) {
+                unsafe {
+                    if self.deleted_cnt > 0 {
+                        let ptr = self.v.as_mut_ptr();
+                        ptr::copy(
+                            ptr.add(self.processed_len),
+                            ptr.add(self.processed_len - self.deleted_cnt),
+                            self.original_len - self.processed_len,
+                        );
+                    }
+                    self.v.set_len(self.original_len - self.deleted_cnt);
+                }
+            }
+        }
+
+        let mut g = BackshiftOnDrop {
+            v: self,
+            processed_len: 0,
+            deleted_cnt: 0,
+            original_len,
+        };
+
+        while g.processed_len != original_len {
+            let cur = unsafe { g.v.as_mut_ptr().add(g.processed_len) };
+            if !f(unsafe { &mut *cur }) {
+                g.processed_len += 1;
+                g.deleted_cnt += 1;
+                unsafe { ptr::drop_in_place(cur) };
+                continue;
+            }
+            if g.deleted_cnt > 0 {
+                unsafe {
+                    let hole = g.v.as_mut_ptr().add(g.processed_len - g.deleted_cnt);
+                    ptr::copy_nonoverlapping(cur, hole, 1);
+                }
+            }
+            g.processed_len += 1;
+        }
+    }
+
+    /// Rotates the vector in place so that the element at `mid` becomes the first.
+    ///
+    /// # Panics
+    /// Panics if `mid > len`.
+    pub fn rotate_left(&mut self, mid: usize) {
+        assert!(mid <= self.len, "mid out of bounds");
+        let k = self.len - mid;
+        // SAFETY: [0..mid] and [mid..len] are within the vector
+        unsafe { ptr_rotate(mid, self.as_mut_ptr().add(mid), k) }
+    }
+
+    /// Rotates the vector in place so that the last `k` elements come first.
+    ///
+    /// # Panics
+    /// Panics if `k > len`.
+    pub fn rotate_right(&mut self, k: usize) {
+        assert!(k <= self.len, "k out of bounds");
+        let mid = self.len - k;
+        // SAFETY: [0..mid] and [mid..len] are within the vector
+        unsafe { ptr_rotate(mid, self.as_mut_ptr().add(mid), k) }
+    }
+
+    pub(super) fn extend_desugared<I: Iterator<Item = T>>(&mut self, mut iterator: I) {
+        // reserving the lower bound up front allocates exactly once for exact-size iterators
+        let (lower, _) = iterator.size_hint();
+        self.reserve(lower);
+
         // Main loop after optimizations taken from Vec::extend_desugared.
         // Writes the rest of the elements from the iterator to the vector.
         while let Some(element) = iterator.next() {
@@ -213,14 +812,45 @@ impl<T> Vec<T> {
                 self.reserve(lower.saturating_add(1));
             }
             unsafe {
-                ptr::write(self.as_mut_ptr(), element);
+                ptr::write(self.as_mut_ptr().add(len), element);
                 self.set_len(len + 1);
             }
         }
     }
 }
 
-impl<T> Deref for Vec<T> {
+impl<T: Clone, A: Allocator, G: GrowthPolicy> Vec<T, A, G> {
+    pub fn resize(&mut self, new_len: usize, value: T) {
+        let len = self.len;
+        if new_len > len {
+            self.extend_with(new_len - len, value);
+        } else {
+            self.truncate(new_len);
+        }
+    }
+
+    fn extend_with(&mut self, n: usize, value: T) {
+        if n == 0 {
+            return;
+        }
+        self.reserve(n);
+
+        // write n - 1 clones, then move the value itself into the last slot
+        // the length is bumped after every write, so a panicking clone leaks nothing
+        unsafe {
+            let mut p = self.as_mut_ptr().add(self.len);
+            for _ in 1..n {
+                ptr::write(p, value.clone());
+                p = p.add(1);
+                self.len += 1;
+            }
+            ptr::write(p, value);
+            self.len += 1;
+        }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Deref for Vec<T, A, G> {
     type Target = [T];
 
     #[inline]
@@ -229,57 +859,58 @@ impl<T> Deref for Vec<T> {
     }
 }
 
-impl<T> DerefMut for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> DerefMut for Vec<T, A, G> {
     #[inline]
     fn deref_mut(&mut self) -> &mut [T] {
         unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), self.len()) }
     }
 }
 
-impl<T> Default for Vec<T> {
+impl<T, G: GrowthPolicy> Default for Vec<T, Global, G> {
     #[inline]
     fn default() -> Self {
         Vec::new()
     }
 }
 
-impl<T> AsRef<Vec<T>> for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> AsRef<Vec<T, A, G>> for Vec<T, A, G> {
     #[inline]
-    fn as_ref(&self) -> &Vec<T> {
+    fn as_ref(&self) -> &Vec<T, A, G> {
         self
     }
 }
 
-impl<T> AsMut<Vec<T>> for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> AsMut<Vec<T, A, G>> for Vec<T, A, G> {
     #[inline]
-    fn as_mut(&mut self) -> &mut Vec<T> {
+    fn as_mut(&mut self) -> &mut Vec<T, A, G> {
         self
     }
 }
 
-impl<T> AsRef<[T]> for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> AsRef<[T]> for Vec<T, A, G> {
     #[inline]
     fn as_ref(&self) -> &[T] {
         self
     }
 }
 
-impl<T> AsMut<[T]> for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> AsMut<[T]> for Vec<T, A, G> {
     #[inline]
     fn as_mut(&mut self) -> &mut [T] {
         self
     }
 }
 
-impl<T> IntoIterator for Vec<T> {
+impl<T, A: Allocator, G: GrowthPolicy> IntoIterator for Vec<T, A, G> {
     type Item = T;
-    type IntoIter = IntoIter<T>;
+    type IntoIter = IntoIter<T, A>;
     fn into_iter(self) -> Self::IntoIter {
         unsafe {
             let mut m = mem::ManuallyDrop::new(self);
+            let alloc = ManuallyDrop::new(ptr::read(m.allocator()));
             let ptr = m.as_mut_ptr();
             let end: *const T = if mem::size_of::<T>() == 0 {
-                ptr.wrapping_offset(m.len() as isize)
+                ptr.wrapping_byte_add(m.len())
             } else {
                 ptr.add(m.len())
             };
@@ -288,6 +919,7 @@ impl<T> IntoIterator for Vec<T> {
                 buf: NonNull::new_unchecked(ptr),
                 phantom: PhantomData,
                 cap,
+                alloc,
                 ptr,
                 end,
             }
@@ -295,47 +927,41 @@ impl<T> IntoIterator for Vec<T> {
     }
 }
 
-impl<'a, T> IntoIterator for &'a Vec<T> {
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a Vec<T, A, G> {
     type Item = &'a T;
-    type IntoIter = slice::Iter<'a, T>;
+    type IntoIter = Iter<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
-        let v = *self;
+        self.iter()
     }
 }
 
-impl<'a, T> IntoIterator for &'a mut Vec<T> {
+impl<'a, T, A: Allocator, G: GrowthPolicy> IntoIterator for &'a mut Vec<T, A, G> {
     type Item = &'a mut T;
-    type IntoIter = slice::IterMut<'a, T>;
+    type IntoIter = IterMut<'a, T>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter_mut()
     }
 }
 
-impl<T> FromIterator<T> for Vec<T> {
-    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut iterator = iter.into_iter();
+impl<T, A: Allocator, G: GrowthPolicy> Extend<T> for Vec<T, A, G> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        <Self as SpecExtend<T, I::IntoIter>>::spec_extend(self, iter.into_iter())
+    }
+}
 
-        // Light optimization taken from SpecFromIterNested::from_iter.
-        // Determines initial capacity using a size hint.
-        let mut vector = match iterator.next() {
-            None => Vec::new(),
-            Some(element) => {
-                let (lower, _) = iterator.size_hint();
-                let initial_capacity =
-                    cmp::max(RawVec::<T>::MIN_NON_ZERO_CAP, lower.saturating_add(1));
-                let mut vector = Vec::with_capacity(initial_capacity);
-                unsafe {
-                    ptr::write(vector.as_mut_ptr(), element);
-                    vector.set_len(1);
-                }
-                vector
-            }
-        };
+impl<'a, T: Copy + 'a, A: Allocator, G: GrowthPolicy> Extend<&'a T> for Vec<T, A, G> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.spec_extend(iter.into_iter())
+    }
+}
 
-        vector.extend(iterator);
-        vector
+impl<T, G: GrowthPolicy> FromIterator<T> for Vec<T, Global, G> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        <Self as SpecFromIter<T, I::IntoIter>>::from_iter(iter.into_iter())
     }
 }
 
@@ -351,8 +977,8 @@ impl<T: Copy> From<&mut [T]> for Vec<T> {
     }
 }
 
-impl<T> From<Box<[T]>> for Vec<T> {
-    fn from(value: Box<[T]>) -> Self {
+impl<T, A: Allocator> From<Box<[T], A>> for Vec<T, A> {
+    fn from(value: Box<[T], A>) -> Self {
         box_into_vec(value)
     }
 }
@@ -363,7 +989,154 @@ impl From<&str> for Vec<u8> {
     }
 }
 
-impl<T> Drop for Vec<T> {
+impl<T, A: Allocator> From<std::vec::Vec<T, A>> for Vec<T, A> {
+    /// Takes over the allocation of a `std::vec::Vec` without copying.
+    fn from(value: std::vec::Vec<T, A>) -> Self {
+        let (ptr, len, cap, alloc) = value.into_raw_parts_with_alloc();
+        // SAFETY: both vectors allocate with Layout::array::<T>(cap) through the same allocator
+        unsafe { Vec::from_raw_parts_in(ptr, len, cap, alloc) }
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> From<Vec<T, A, G>> for std::vec::Vec<T, A> {
+    /// Hands the allocation over to a `std::vec::Vec` without copying.
+    fn from(value: Vec<T, A, G>) -> Self {
+        let (ptr, len, cap, alloc) = value.into_raw_parts_with_alloc();
+        // SAFETY: both vectors allocate with Layout::array::<T>(cap) through the same allocator
+        unsafe { std::vec::Vec::from_raw_parts_in(ptr, len, cap, alloc) }
+    }
+}
+
+impl<T, G: GrowthPolicy> From<Vec<T, Global, G>> for Box<[T]> {
+    fn from(value: Vec<T, Global, G>) -> Self {
+        value.into_boxed_slice()
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone, G: GrowthPolicy> Clone for Vec<T, A, G> {
+    fn clone(&self) -> Self {
+        let alloc = self.allocator().clone();
+        let mut v = Self::with_capacity_in(self.len, alloc);
+        v.extend(self.iter().cloned());
+        v
+    }
+}
+
+impl<T: Debug, A: Allocator, G: GrowthPolicy> Debug for Vec<T, A, G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(&**self, f)
+    }
+}
+
+impl<T: Hash, A: Allocator, G: GrowthPolicy> Hash for Vec<T, A, G> {
+    /// Hashes the same way as the equivalent slice, so that `Borrow<[T]>` lookups agree.
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Hash::hash(&**self, state)
+    }
+}
+
+macro_rules! impl_slice_eq {
+    ([$($vars:tt)*] $lhs:ty, $rhs:ty) => {
+        impl<T, U, $($vars)*> PartialEq<$rhs> for $lhs
+        where
+            T: PartialEq<U>,
+        {
+            #[inline]
+            fn eq(&self, other: &$rhs) -> bool {
+                self[..] == other[..]
+            }
+        }
+    };
+}
+
+impl_slice_eq! { [A1: Allocator, G1: GrowthPolicy, A2: Allocator, G2: GrowthPolicy] Vec<T, A1, G1>, Vec<U, A2, G2> }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] Vec<T, A, G>, [U] }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] Vec<T, A, G>, &[U] }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] Vec<T, A, G>, &mut [U] }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy, const N: usize] Vec<T, A, G>, [U; N] }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy, const N: usize] Vec<T, A, G>, &[U; N] }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] [T], Vec<U, A, G> }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] &[T], Vec<U, A, G> }
+impl_slice_eq! { [A: Allocator, G: GrowthPolicy] &mut [T], Vec<U, A, G> }
+impl_slice_eq! { [A1: Allocator, G: GrowthPolicy, A2: Allocator] Vec<T, A1, G>, std::vec::Vec<U, A2> }
+impl_slice_eq! { [A1: Allocator, A2: Allocator, G: GrowthPolicy] std::vec::Vec<T, A1>, Vec<U, A2, G> }
+
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, A: Allocator, G: GrowthPolicy> serde::Serialize for Vec<T, A, G> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>, G: GrowthPolicy> serde::Deserialize<'de>
+    for Vec<T, Global, G>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct VecVisitor<T, G>(PhantomData<(T, G)>);
+
+        impl<'de, T: serde::Deserialize<'de>, G: GrowthPolicy> serde::de::Visitor<'de>
+            for VecVisitor<T, G>
+        {
+            type Value = Vec<T, Global, G>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a sequence")
+            }
+
+            fn visit_seq<S: serde::de::SeqAccess<'de>>(
+                self,
+                mut seq: S,
+            ) -> Result<Self::Value, S::Error> {
+                // the hint comes from the input, so cap it at 1 MiB rather than trusting it
+                const MAX_PREALLOC_BYTES: usize = 1024 * 1024;
+                let hint = seq.size_hint().unwrap_or(0);
+                let capacity =
+                    cmp::min(hint, MAX_PREALLOC_BYTES / cmp::max(mem::size_of::<T>(), 1));
+
+                let mut values = Self::Value::try_with_capacity(capacity)
+                    .map_err(serde::de::Error::custom)?;
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(values)
+            }
+        }
+
+        deserializer.deserialize_seq(VecVisitor(PhantomData))
+    }
+}
+
+impl<T: Eq, A: Allocator, G: GrowthPolicy> Eq for Vec<T, A, G> {}
+
+impl<T: PartialOrd, A: Allocator, G: GrowthPolicy> PartialOrd for Vec<T, A, G> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        PartialOrd::partial_cmp(&**self, &**other)
+    }
+}
+
+impl<T: Ord, A: Allocator, G: GrowthPolicy> Ord for Vec<T, A, G> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        Ord::cmp(&**self, &**other)
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Borrow<[T]> for Vec<T, A, G> {
+    fn borrow(&self) -> &[T] {
+        self
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> BorrowMut<[T]> for Vec<T, A, G> {
+    fn borrow_mut(&mut self) -> &mut [T] {
+        self
+    }
+}
+
+impl<T, A: Allocator, G: GrowthPolicy> Drop for Vec<T, A, G> {
     #[inline]
     fn drop(&mut self) {
         unsafe {
@@ -373,7 +1146,7 @@ impl<T> Drop for Vec<T> {
     }
 }
 
-impl<T, I: SliceIndex<[T]>> Index<I> for Vec<T> {
+impl<T, I: SliceIndex<[T]>, A: Allocator, G: GrowthPolicy> Index<I> for Vec<T, A, G> {
     type Output = I::Output;
 
     #[inline]
@@ -382,7 +1155,7 @@ impl<T, I: SliceIndex<[T]>> Index<I> for Vec<T> {
     }
 }
 
-impl<T, I: SliceIndex<[T]>> IndexMut<I> for Vec<T> {
+impl<T, I: SliceIndex<[T]>, A: Allocator, G: GrowthPolicy> IndexMut<I> for Vec<T, A, G> {
     #[inline]
     fn index_mut(&mut self, index: I) -> &mut Self::Output {
         IndexMut::index_mut(&mut **self, index)
@@ -398,8 +1171,32 @@ fn to_vec_copy<T: Copy>(s: &[T]) -> Vec<T> {
     v
 }
 
-fn box_into_vec<T>(b: Box<[T]>) -> Vec<T> {
+fn box_into_vec<T, A: Allocator>(b: Box<[T], A>) -> Vec<T, A> {
     let len = b.len();
-    let b = Box::into_raw(b);
-    Vec::from_raw_parts(b as *mut T, len, len)
+    let (b, alloc) = Box::into_raw_with_allocator(b);
+    // SAFETY: a boxed slice of len elements is allocated with Layout::array::<T>(len)
+    unsafe { Vec::from_raw_parts_in(b as *mut T, len, len, alloc) }
+}
+
+/// Rotates the `left + right` elements around `mid`, so that the element at `mid` comes first.
+/// Swaps the shorter side into its final place and repeats with what's left (Gries-Mills).
+///
+/// # Safety
+/// `mid - left .. mid + right` must be valid for reads and writes.
+unsafe fn ptr_rotate<T>(mut left: usize, mut mid: *mut T, mut right: usize) {
+    while left > 0 && right > 0 {
+        unsafe {
+            if left <= right {
+                // [A B1 B2] -> [B1 A B2], then rotate [A B2]
+                ptr::swap_nonoverlapping(mid.sub(left), mid, left);
+                mid = mid.add(left);
+                right -= left;
+            } else {
+                // [A1 A2 B] -> [A1 B A2], then rotate [A1 B]
+                ptr::swap_nonoverlapping(mid.sub(right), mid, right);
+                mid = mid.sub(right);
+                left -= right;
+            }
+        }
+    }
 }
@@ -0,0 +1,51 @@
+//! Helpers shared by the test modules.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Deterministic pseudo-random numbers, so failures can be reproduced.
+pub fn xorshift(seed: &mut u32) -> u32 {
+    *seed ^= *seed << 13;
+    *seed ^= *seed >> 17;
+    *seed ^= *seed << 5;
+    *seed
+}
+
+/// Counts the [`DropCounter`]s dropped so far. Atomic, so the counters can be dropped on other
+/// threads, and `new` is const, so it can be a `static`.
+#[derive(Debug, Default)]
+pub struct Drops(AtomicUsize);
+
+impl Drops {
+    pub const fn new() -> Self {
+        Self(AtomicUsize::new(0))
+    }
+
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Bumps its [`Drops`] when dropped, with an optional `value` to sort or compare by.
+#[derive(Debug)]
+pub struct DropCounter<'a, T = ()> {
+    pub value: T,
+    drops: &'a Drops,
+}
+
+impl<'a> DropCounter<'a> {
+    pub fn new(drops: &'a Drops) -> Self {
+        Self::with_value((), drops)
+    }
+}
+
+impl<'a, T> DropCounter<'a, T> {
+    pub fn with_value(value: T, drops: &'a Drops) -> Self {
+        Self { value, drops }
+    }
+}
+
+impl<T> Drop for DropCounter<'_, T> {
+    fn drop(&mut self) {
+        self.drops.0.fetch_add(1, Ordering::Relaxed);
+    }
+}
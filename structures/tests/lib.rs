@@ -1,2 +1,7 @@
+#![feature(allocator_api)]
+#![feature(arbitrary_self_types)]
+mod common;
 mod pointer;
-mod vector;
\ No newline at end of file
+mod queue;
+mod string;
+mod vector;
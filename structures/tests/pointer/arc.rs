@@ -0,0 +1,233 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Barrier, Mutex};
+use std::thread;
+
+use structures::pointer::arc::{Arc, Weak};
+
+use crate::common::{DropCounter, Drops};
+
+/// Stands in for the singleton pattern's `SaferDatabase`: shared immutably, counted atomically.
+struct Database {
+    queries: AtomicUsize,
+}
+
+impl Database {
+    fn query(&self, _query: &str) {
+        self.queries.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+#[test]
+fn is_send_and_sync() {
+    assert_send_sync::<Arc<i32>>();
+    assert_send_sync::<Weak<i32>>();
+    assert_send_sync::<Arc<Mutex<i32>>>();
+    assert_send_sync::<Arc<dyn Display + Send + Sync>>();
+}
+
+#[test]
+fn shared_across_threads() {
+    let database = Arc::new(Database {
+        queries: AtomicUsize::new(0),
+    });
+
+    let mut handles = Vec::new();
+    for i in 1..=100 {
+        let db = Arc::clone(&database);
+        handles.push(thread::spawn(move || db.query(&format!("from thread {i}"))));
+    }
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    assert_eq!(database.queries.load(Ordering::Relaxed), 100);
+    assert_eq!(Arc::strong_count(&database), 1);
+}
+
+#[test]
+fn shared_mutex_across_threads() {
+    let counter = Arc::new(Mutex::new(0));
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let counter = counter.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    *counter.lock().unwrap() += 1;
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(*counter.lock().unwrap(), 8000);
+}
+
+#[test]
+fn drops_value_once_across_threads() {
+    static DROPS: Drops = Drops::new();
+    let value = Arc::new(DropCounter::new(&DROPS));
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let value = value.clone();
+            thread::spawn(move || {
+                let clones: Vec<_> = (0..100).map(|_| value.clone()).collect();
+                drop(clones);
+            })
+        })
+        .collect();
+    drop(value);
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(DROPS.get(), 1);
+}
+
+#[test]
+fn into_inner_gives_value_to_exactly_one_thread() {
+    for _ in 0..50 {
+        let value = Arc::new(String::from("foo"));
+        let barrier = Arc::new(Barrier::new(4));
+        let mut clones: Vec<_> = (0..3).map(|_| value.clone()).collect();
+        clones.push(value);
+        let handles: Vec<_> = clones
+            .into_iter()
+            .map(|value| {
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    Arc::into_inner(value)
+                })
+            })
+            .collect();
+
+        let got: Vec<_> = handles
+            .into_iter()
+            .filter_map(|handle| handle.join().unwrap())
+            .collect();
+        assert_eq!(got, ["foo"]);
+    }
+}
+
+#[test]
+fn weak_upgrade() {
+    let a = Arc::new(5);
+    let weak = Arc::downgrade(&a);
+    assert_eq!(Arc::weak_count(&a), 1);
+    assert_eq!(*weak.upgrade().unwrap(), 5);
+
+    drop(a);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(weak.weak_count(), 0);
+
+    let empty: Weak<i32> = Weak::new();
+    assert!(empty.upgrade().is_none());
+    assert!(empty.clone().ptr_eq(&empty));
+}
+
+#[test]
+fn weak_upgrade_races_with_drop() {
+    for _ in 0..50 {
+        let value = Arc::new(7);
+        let weak = Arc::downgrade(&value);
+        let handle = thread::spawn(move || {
+            // either sees the value or nothing, never a dropped one
+            if let Some(value) = weak.upgrade() {
+                assert_eq!(*value, 7);
+            }
+        });
+        drop(value);
+        handle.join().unwrap();
+    }
+}
+
+#[test]
+fn get_mut() {
+    let mut a = Arc::new(3);
+    *Arc::get_mut(&mut a).unwrap() = 4;
+    assert_eq!(*a, 4);
+
+    let b = a.clone();
+    assert!(Arc::get_mut(&mut a).is_none());
+    drop(b);
+
+    let weak = Arc::downgrade(&a);
+    assert!(Arc::get_mut(&mut a).is_none());
+    drop(weak);
+    assert!(Arc::get_mut(&mut a).is_some());
+}
+
+#[test]
+fn make_mut() {
+    let mut a = Arc::new(5);
+    let b = a.clone();
+    *Arc::make_mut(&mut a) += 1;
+    assert_eq!((*a, *b), (6, 5));
+    assert!(!Arc::ptr_eq(&a, &b));
+
+    let before = Arc::as_ptr(&a);
+    *Arc::make_mut(&mut a) += 1;
+    assert_eq!(Arc::as_ptr(&a), before);
+    assert_eq!(*a, 7);
+
+    let weak = Arc::downgrade(&a);
+    *Arc::make_mut(&mut a) += 1;
+    assert!(weak.upgrade().is_none());
+    assert_eq!(Arc::weak_count(&a), 0);
+    assert_eq!(*a, 8);
+}
+
+#[test]
+fn try_unwrap_and_unwrap_or_clone() {
+    let a = Arc::new(3);
+    assert_eq!(Arc::try_unwrap(a), Ok(3));
+
+    let a = Arc::new(4);
+    let b = a.clone();
+    let a = Arc::try_unwrap(a).unwrap_err();
+    assert_eq!(Arc::unwrap_or_clone(a), 4);
+    assert_eq!(Arc::unwrap_or_clone(b), 4);
+}
+
+#[test]
+fn new_cyclic() {
+    struct Node {
+        me: Weak<Node>,
+    }
+
+    let node = Arc::new_cyclic(|me| {
+        assert!(me.upgrade().is_none());
+        Node { me: me.clone() }
+    });
+    assert!(Arc::ptr_eq(&node.me.upgrade().unwrap(), &node));
+    assert_eq!(Arc::weak_count(&node), 1);
+}
+
+#[test]
+fn unsize_to_trait_object() {
+    let a: Arc<dyn Display + Send + Sync> = Arc::new(42);
+    let weak: Weak<dyn Display + Send + Sync> = Arc::downgrade(&a);
+    let handle = thread::spawn(move || a.to_string());
+    assert_eq!(handle.join().unwrap(), "42");
+    assert!(weak.upgrade().is_none());
+
+    let drops = Drops::new();
+    let slice: Arc<[DropCounter<'_>]> =
+        Arc::new([DropCounter::new(&drops), DropCounter::new(&drops)]);
+    assert_eq!(slice.len(), 2);
+    drop(slice);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn delegates_traits() {
+    let a = Arc::new(1);
+    assert!(a < Arc::new(2));
+    assert_eq!(a, Arc::from(1));
+    assert_eq!(format!("{a} {a:?}"), "1 1");
+    assert_eq!(*Arc::<i32>::default(), 0);
+}
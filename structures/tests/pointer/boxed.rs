@@ -0,0 +1,180 @@
+use std::fmt::Display;
+use std::mem::MaybeUninit;
+
+use structures::pointer::Box;
+use structures::vector::Vec;
+
+use crate::common::{DropCounter, Drops};
+
+#[test]
+fn new_and_deref() {
+    let mut b = Box::new(5);
+    assert_eq!(*b, 5);
+    *b += 1;
+    assert_eq!(*b, 6);
+    assert_eq!(Box::into_inner(b), 6);
+}
+
+#[test]
+fn drops_value_once() {
+    let drops = Drops::new();
+    let b = Box::new(DropCounter::new(&drops));
+    drop(b);
+    assert_eq!(drops.get(), 1);
+
+    let b = Box::new(DropCounter::new(&drops));
+    let inner = Box::into_inner(b);
+    assert_eq!(drops.get(), 1);
+    drop(inner);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn zero_sized() {
+    let drops = Drops::new();
+    struct Zst;
+    let b = Box::new(Zst);
+    let _ = Box::into_inner(b);
+
+    let b: Box<[DropCounter<'_>; 0]> = Box::new([]);
+    let b: Box<[DropCounter<'_>]> = b;
+    assert!(b.is_empty());
+    drop(b);
+    assert_eq!(drops.get(), 0);
+}
+
+#[test]
+fn into_raw_from_raw() {
+    let b = Box::new(String::from("foo"));
+    let raw = Box::into_raw(b);
+    unsafe {
+        (*raw).push_str("bar");
+        let b = Box::from_raw(raw);
+        assert_eq!(*b, "foobar");
+    }
+}
+
+#[test]
+fn leak() {
+    let leaked: &'static mut i32 = Box::leak(Box::new(41));
+    *leaked += 1;
+    assert_eq!(*leaked, 42);
+    // reclaim it so the test doesn't leak
+    drop(unsafe { Box::from_raw(leaked) });
+}
+
+#[test]
+fn new_uninit() {
+    let mut b = Box::<u64>::new_uninit();
+    b.write(7);
+    let b = unsafe { b.assume_init() };
+    assert_eq!(*b, 7);
+
+    let b: Box<MaybeUninit<String>> = Box::new_uninit();
+    let b = Box::write(b, String::from("written"));
+    assert_eq!(*b, "written");
+}
+
+#[test]
+fn unsize_to_trait_object() {
+    let b: Box<dyn Display> = Box::new(42);
+    assert_eq!(b.to_string(), "42");
+
+    let drops = Drops::new();
+    trait Named {
+        fn name(&self) -> &str;
+    }
+    struct Dog<'a>(#[allow(dead_code)] DropCounter<'a>);
+    impl Named for Dog<'_> {
+        fn name(&self) -> &str {
+            "dog"
+        }
+    }
+    let animals: std::vec::Vec<Box<dyn Named + '_>> = vec![
+        Box::new(Dog(DropCounter::new(&drops))),
+        Box::new(Dog(DropCounter::new(&drops))),
+    ];
+    assert!(animals.iter().all(|animal| animal.name() == "dog"));
+    drop(animals);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn unsize_to_slice() {
+    let mut b: Box<[i32]> = Box::new([1, 2, 3]);
+    b[1] = 5;
+    assert_eq!(&*b, [1, 5, 3]);
+    assert_eq!(b.clone(), b);
+}
+
+#[test]
+fn dyn_dispatch_by_box_receiver() {
+    trait Consume {
+        fn consume(self: Box<Self>) -> String;
+    }
+    struct Token(String);
+    impl Consume for Token {
+        fn consume(self: Box<Self>) -> String {
+            Box::into_inner(self).0
+        }
+    }
+
+    let token: Box<dyn Consume> = Box::new(Token(String::from("spent")));
+    assert_eq!(token.consume(), "spent");
+}
+
+#[test]
+fn vec_round_trip() {
+    let mut vec: Vec<_> = Vec::with_capacity(10);
+    vec.extend([1, 2, 3]);
+    let boxed: Box<[i32]> = Box::from(vec);
+    assert_eq!(&*boxed, [1, 2, 3]);
+
+    let ptr = Box::as_ptr(&boxed) as *const i32;
+    let vec: Vec<i32> = Vec::from(boxed);
+    assert_eq!(vec.as_ptr(), ptr);
+    assert_eq!(vec.len(), 3);
+    assert_eq!(vec.capacity(), 3);
+    assert_eq!(vec, [1, 2, 3]);
+}
+
+#[test]
+fn vec_round_trip_drops_elements() {
+    let drops = Drops::new();
+    let mut vec: Vec<_> = Vec::new();
+    vec.push(DropCounter::new(&drops));
+    vec.push(DropCounter::new(&drops));
+    let boxed: Box<[DropCounter<'_>]> = vec.into();
+    let vec: Vec<DropCounter<'_>> = boxed.into();
+    assert_eq!(drops.get(), 0);
+    drop(vec);
+    assert_eq!(drops.get(), 2);
+
+    let empty: Box<[String]> = Vec::<String>::new().into();
+    assert!(empty.is_empty());
+    assert!(Vec::from(empty).is_empty());
+
+    let units: Box<[()]> = Box::from(&[(), ()][..]);
+    assert_eq!(Vec::from(units).len(), 2);
+}
+
+#[test]
+fn std_box_round_trip() {
+    let b: Box<[i32]> = std::boxed::Box::<[i32]>::from([1, 2]).into();
+    assert_eq!(&*b, [1, 2]);
+    let std_box: std::boxed::Box<[i32]> = Box::into_std(b);
+    assert_eq!(&*std_box, [1, 2]);
+}
+
+#[test]
+fn delegates_traits() {
+    let a = Box::new(1);
+    assert!(a < Box::new(2));
+    assert_eq!(a, Box::from(1));
+    assert_eq!(format!("{a} {a:?}"), "1 1");
+    assert_eq!(*Box::<i32>::default(), 0);
+
+    let mut iter: Box<dyn Iterator<Item = i32>> = Box::new(0..3);
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.sum::<i32>(), 3);
+}
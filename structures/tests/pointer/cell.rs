@@ -139,6 +139,7 @@ fn cell_does_not_clone() {
         x: isize,
     }
 
+    #[allow(clippy::non_canonical_clone_impl)]
     impl Clone for Foo {
         fn clone(&self) -> Foo {
             panic!();
@@ -172,4 +173,14 @@ fn cell_update() {
 
     assert_eq!(x.update(|x| x / 3), 5);
     assert_eq!(x.get(), 5);
-}
\ No newline at end of file
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_cell() {
+    let cell = Cell::new((1, 2.5));
+    let json = serde_json::to_string(&cell).unwrap();
+    assert_eq!(json, "[1,2.5]");
+    let back: Cell<(i32, f64)> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, cell);
+}
@@ -1,3 +1,6 @@
 mod unsafecell;
 mod cell;
-mod refcell;
\ No newline at end of file
+mod refcell;
+mod rc;
+mod arc;
+mod boxed;
\ No newline at end of file
@@ -0,0 +1,266 @@
+use std::fmt::Display;
+
+use structures::pointer::rc::{Rc, Weak};
+use structures::pointer::{Cell, RefCell};
+
+use crate::common::{DropCounter, Drops};
+
+#[test]
+fn clone_shares_value() {
+    let a = Rc::new(5);
+    let b = a.clone();
+    assert_eq!(*a, 5);
+    assert_eq!(*b, 5);
+    assert!(Rc::ptr_eq(&a, &b));
+    assert_eq!(Rc::strong_count(&a), 2);
+    drop(b);
+    assert_eq!(Rc::strong_count(&a), 1);
+}
+
+#[test]
+fn drops_value_once() {
+    let drops = Drops::new();
+    let a = Rc::new(DropCounter::new(&drops));
+    let b = a.clone();
+    drop(a);
+    assert_eq!(drops.get(), 0);
+    drop(b);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn weak_upgrade() {
+    let a = Rc::new(String::from("foo"));
+    let weak = Rc::downgrade(&a);
+    assert_eq!(Rc::weak_count(&a), 1);
+    assert_eq!(weak.strong_count(), 1);
+    assert_eq!(*weak.upgrade().unwrap(), "foo");
+
+    drop(a);
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.strong_count(), 0);
+    assert_eq!(weak.weak_count(), 0);
+}
+
+#[test]
+fn weak_outlives_value() {
+    let drops = Drops::new();
+    let a = Rc::new(DropCounter::new(&drops));
+    let w1 = Rc::downgrade(&a);
+    let w2 = w1.clone();
+    drop(a);
+    assert_eq!(drops.get(), 1);
+    assert!(w1.ptr_eq(&w2));
+    drop(w1);
+    drop(w2);
+}
+
+#[test]
+fn weak_new_never_upgrades() {
+    let weak: Weak<i32> = Weak::new();
+    assert!(weak.upgrade().is_none());
+    assert_eq!(weak.strong_count(), 0);
+    let clone = weak.clone();
+    assert!(clone.ptr_eq(&weak));
+    assert!(Weak::<i32>::default().upgrade().is_none());
+}
+
+#[test]
+fn get_mut() {
+    let mut a = Rc::new(3);
+    *Rc::get_mut(&mut a).unwrap() = 4;
+    assert_eq!(*a, 4);
+
+    let b = a.clone();
+    assert!(Rc::get_mut(&mut a).is_none());
+    drop(b);
+
+    let weak = Rc::downgrade(&a);
+    assert!(Rc::get_mut(&mut a).is_none());
+    drop(weak);
+    assert!(Rc::get_mut(&mut a).is_some());
+}
+
+#[test]
+fn make_mut_clones_when_shared() {
+    let mut a = Rc::new(5);
+    let b = a.clone();
+    *Rc::make_mut(&mut a) += 1;
+    assert_eq!(*a, 6);
+    assert_eq!(*b, 5);
+    assert!(!Rc::ptr_eq(&a, &b));
+
+    // already unique, so no clone
+    let before = Rc::as_ptr(&a);
+    *Rc::make_mut(&mut a) += 1;
+    assert_eq!(Rc::as_ptr(&a), before);
+    assert_eq!(*a, 7);
+}
+
+#[test]
+fn make_mut_disassociates_weaks() {
+    let drops = Cell::new(0);
+
+    #[derive(Clone)]
+    struct Counted<'a>(i32, &'a Cell<usize>);
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.1.set(self.1.get() + 1);
+        }
+    }
+
+    let mut a = Rc::new(Counted(1, &drops));
+    let weak = Rc::downgrade(&a);
+    Rc::make_mut(&mut a).0 = 2;
+
+    assert!(weak.upgrade().is_none());
+    assert_eq!(Rc::weak_count(&a), 0);
+    assert_eq!(a.0, 2);
+    // the value was moved, not cloned, so nothing was dropped
+    assert_eq!(drops.get(), 0);
+
+    drop(weak);
+    drop(a);
+    assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn try_unwrap() {
+    let a = Rc::new(3);
+    assert_eq!(Rc::try_unwrap(a), Ok(3));
+
+    let a = Rc::new(4);
+    let b = a.clone();
+    let a = Rc::try_unwrap(a).unwrap_err();
+    assert_eq!(*a, 4);
+    drop(b);
+
+    let weak = Rc::downgrade(&a);
+    assert_eq!(Rc::into_inner(a), Some(4));
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn unwrap_or_clone() {
+    let a = Rc::new(String::from("foo"));
+    let b = a.clone();
+    assert_eq!(Rc::unwrap_or_clone(a), "foo");
+    assert_eq!(Rc::unwrap_or_clone(b), "foo");
+}
+
+#[test]
+fn new_cyclic() {
+    struct Node {
+        me: Weak<Node>,
+        value: i32,
+    }
+
+    let node = Rc::new_cyclic(|me| {
+        assert!(me.upgrade().is_none());
+        Node {
+            me: me.clone(),
+            value: 7,
+        }
+    });
+    let me = node.me.upgrade().unwrap();
+    assert!(Rc::ptr_eq(&me, &node));
+    assert_eq!(me.value, 7);
+    assert_eq!(Rc::strong_count(&node), 2);
+    assert_eq!(Rc::weak_count(&node), 1);
+}
+
+#[test]
+#[should_panic]
+fn new_cyclic_panic_frees_allocation() {
+    let _: Rc<i32> = Rc::new_cyclic(|_| panic!());
+}
+
+#[test]
+fn cycle_through_refcell_is_broken_by_weak() {
+    struct Parent<'a> {
+        children: RefCell<std::vec::Vec<Rc<Child<'a>>>>,
+        _drops: DropCounter<'a>,
+    }
+    struct Child<'a> {
+        parent: RefCell<Weak<Parent<'a>>>,
+        _drops: DropCounter<'a>,
+    }
+
+    let drops = Drops::new();
+    let parent = Rc::new(Parent {
+        children: RefCell::new(std::vec::Vec::new()),
+        _drops: DropCounter::new(&drops),
+    });
+    let child = Rc::new(Child {
+        parent: RefCell::new(Weak::new()),
+        _drops: DropCounter::new(&drops),
+    });
+    *child.parent.borrow_mut() = Rc::downgrade(&parent);
+    parent.children.borrow_mut().push(child.clone());
+
+    assert!(child.parent.borrow().upgrade().is_some());
+    drop(parent);
+    assert_eq!(drops.get(), 1);
+    assert!(child.parent.borrow().upgrade().is_none());
+    drop(child);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn unsize_to_trait_object() {
+    let a: Rc<dyn Display> = Rc::new(42);
+    assert_eq!(a.to_string(), "42");
+
+    let weak: Weak<dyn Display> = Rc::downgrade(&a);
+    assert_eq!(weak.upgrade().unwrap().to_string(), "42");
+    drop(a);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn unsize_to_slice() {
+    let drops = Drops::new();
+    let a: Rc<[DropCounter<'_>]> = Rc::new([DropCounter::new(&drops), DropCounter::new(&drops)]);
+    assert_eq!(a.len(), 2);
+    let b = a.clone();
+    drop(a);
+    drop(b);
+    assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn dyn_dispatch_by_rc_receiver() {
+    trait Speak {
+        fn speak(self: Rc<Self>) -> String;
+    }
+    struct Dog;
+    impl Speak for Dog {
+        fn speak(self: Rc<Self>) -> String {
+            String::from("woof")
+        }
+    }
+
+    let dog: Rc<dyn Speak> = Rc::new(Dog);
+    assert_eq!(dog.speak(), "woof");
+}
+
+#[test]
+fn zero_sized_value() {
+    let a = Rc::new(());
+    let weak = Rc::downgrade(&a);
+    let b = a.clone();
+    drop(a);
+    drop(b);
+    assert!(weak.upgrade().is_none());
+}
+
+#[test]
+fn delegates_traits() {
+    let a = Rc::new(1);
+    let b = Rc::new(2);
+    assert!(a < b);
+    assert_eq!(a, Rc::new(1));
+    assert_eq!(format!("{a} {b:?}"), "1 2");
+    assert_eq!(*Rc::<i32>::default(), 0);
+    assert_eq!(*Rc::from(3), 3);
+}
@@ -101,6 +101,7 @@ fn refcell_replace_borrows() {
 }
 
 #[test]
+#[allow(clippy::useless_borrows_in_formatting)]
 fn refcell_format() {
     let name = RefCell::new("rust");
     let what = RefCell::new("rocks");
@@ -156,3 +157,16 @@ fn as_ptr() {
     }
     assert_eq!(1, *r2.borrow());
 }
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_refcell() {
+    let cell = RefCell::new(vec![String::from("a")]);
+    let json = serde_json::to_string(&cell).unwrap();
+    assert_eq!(json, r#"["a"]"#);
+    let back: RefCell<Vec<String>> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, cell);
+
+    let _guard = cell.borrow_mut();
+    assert!(serde_json::to_string(&cell).is_err());
+}
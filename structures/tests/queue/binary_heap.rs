@@ -0,0 +1,176 @@
+use std::cell::Cell;
+use std::mem;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use structures::queue::{BinaryHeap, MinHeap, PeekMut, Queue, QueueError};
+use structures::vector::Vec;
+
+use crate::common::xorshift;
+
+#[test]
+fn push_pop() {
+    let mut seed = 42;
+    let values: std::vec::Vec<u32> = (0..500).map(|_| xorshift(&mut seed) % 100).collect();
+
+    let mut heap = BinaryHeap::new();
+    for &v in &values {
+        heap.push(v);
+    }
+    assert_eq!(heap.len(), 500);
+    assert_eq!(heap.peek(), values.iter().max());
+
+    let mut expected = values.clone();
+    expected.sort_by(|a, b| b.cmp(a));
+    let popped: std::vec::Vec<u32> = std::iter::from_fn(|| heap.pop()).collect();
+    assert_eq!(popped, expected);
+    assert!(heap.is_empty());
+    assert_eq!(heap.peek(), None);
+}
+
+#[test]
+fn heapify_and_sorted_vec() {
+    let mut seed = 3;
+    for len in [0, 1, 2, 3, 10, 257] {
+        let values: Vec<u32> = (0..len).map(|_| xorshift(&mut seed)).collect();
+        let mut expected = values.clone();
+        expected.sort();
+
+        let heap = BinaryHeap::from(values);
+        assert_eq!(heap.into_sorted_vec(), expected);
+    }
+}
+
+#[test]
+fn peek_mut() {
+    let mut heap: BinaryHeap<u32> = [5, 3, 8, 1].into_iter().collect();
+    {
+        let mut top = heap.peek_mut().unwrap();
+        assert_eq!(*top, 8);
+        *top = 2;
+    }
+    assert_eq!(heap.peek(), Some(&5));
+
+    // reading through the guard doesn't reorder anything
+    assert_eq!(*heap.peek_mut().unwrap(), 5);
+    assert_eq!(PeekMut::pop(heap.peek_mut().unwrap()), 5);
+    assert_eq!(heap.into_sorted_vec(), [1, 2, 3]);
+    assert!(BinaryHeap::<u32>::new().peek_mut().is_none());
+}
+
+#[test]
+fn peek_mut_leaked() {
+    let mut heap: BinaryHeap<u32> = [5, 3, 8, 1].into_iter().collect();
+    let mut top = heap.peek_mut().unwrap();
+    *top = 0;
+    mem::forget(top);
+    // the rest of the heap is leaked, but what is left is still in order
+    assert_eq!(heap.len(), 1);
+    assert_eq!(heap.pop(), Some(0));
+    assert!(heap.is_empty());
+}
+
+#[test]
+fn append_and_extend() {
+    let mut a: BinaryHeap<u32> = (0..5).collect();
+    let mut b: BinaryHeap<u32> = (5..100).collect();
+    a.append(&mut b);
+    assert!(b.is_empty());
+    assert_eq!(a.len(), 100);
+
+    a.extend([200, 150]);
+    a.extend(100..110);
+    let sorted = a.into_sorted_vec();
+    assert!(sorted.windows(2).all(|w| w[0] <= w[1]));
+    assert_eq!(sorted.len(), 112);
+    assert_eq!(sorted[111], 200);
+}
+
+#[test]
+fn queue_trait() {
+    let mut heap = BinaryHeap::new();
+    assert!(matches!(
+        Queue::remove(&mut heap),
+        Err(QueueError::EmptyQueue)
+    ));
+    for n in [2, 9, 4] {
+        assert!(heap.add(n).is_ok());
+    }
+    assert_eq!(Queue::peek(&heap), Some(&9));
+    assert!(matches!(Queue::remove(&mut heap), Ok(9)));
+    assert!(matches!(Queue::remove(&mut heap), Ok(4)));
+}
+
+#[test]
+fn min_heap() {
+    let mut heap: MinHeap<u32> = [7, 3, 9].into_iter().collect();
+    heap.push(1);
+    assert_eq!(heap.peek(), Some(&1));
+    assert!(matches!(Queue::remove(&mut heap), Ok(1)));
+    assert_eq!(heap.pop(), Some(3));
+
+    let mut other = MinHeap::from(Vec::from(&[8, 2][..]));
+    heap.append(&mut other);
+    assert_eq!(heap.len(), 4);
+    assert_eq!(heap.into_sorted_vec(), [9, 8, 7, 2]);
+}
+
+struct Panicky<'a> {
+    value: u32,
+    armed: &'a Cell<bool>,
+    drops: &'a Cell<usize>,
+}
+
+impl PartialEq for Panicky<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Eq for Panicky<'_> {}
+
+impl PartialOrd for Panicky<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Panicky<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        assert!(!self.armed.get(), "comparison panicked");
+        self.value.cmp(&other.value)
+    }
+}
+
+impl Drop for Panicky<'_> {
+    fn drop(&mut self) {
+        self.drops.set(self.drops.get() + 1);
+    }
+}
+
+#[test]
+fn panic_safety() {
+    let armed = Cell::new(false);
+    let drops = Cell::new(0);
+    let mut heap: BinaryHeap<Panicky> = (0..20)
+        .map(|value| Panicky {
+            value,
+            armed: &armed,
+            drops: &drops,
+        })
+        .collect();
+
+    armed.set(true);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        heap.push(Panicky {
+            value: 100,
+            armed: &armed,
+            drops: &drops,
+        })
+    }));
+    assert!(result.is_err());
+    armed.set(false);
+
+    // the hole wrote the element back, so nothing was lost or duplicated
+    assert_eq!((heap.len(), drops.get()), (21, 0));
+    drop(heap);
+    assert_eq!(drops.get(), 21);
+}
@@ -0,0 +1,121 @@
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use structures::queue::{BoundedQueue, Queue, QueueError, SyncBoundedQueue};
+
+#[test]
+fn full_queue() {
+    let mut q = BoundedQueue::new(2);
+    assert!(q.add(1).is_ok());
+    assert!(q.add(2).is_ok());
+    assert!(q.is_full());
+    assert!(matches!(q.add(3), Err(QueueError::FullQueue)));
+    assert_eq!(q.push(3), Err(3));
+    assert_eq!(q.peek(), Some(&1));
+
+    assert!(matches!(q.remove(), Ok(1)));
+    assert!(q.add(3).is_ok());
+    assert!(q.iter().copied().eq([2, 3]));
+    assert!(matches!(q.remove(), Ok(2)));
+    assert!(matches!(q.remove(), Ok(3)));
+    assert!(matches!(q.remove(), Err(QueueError::EmptyQueue)));
+    assert_eq!(q.capacity(), 2);
+}
+
+#[test]
+#[should_panic(expected = "capacity must be non-zero")]
+fn zero_capacity() {
+    BoundedQueue::<()>::new(0);
+}
+
+#[test]
+#[should_panic(expected = "capacity must be non-zero")]
+fn zero_capacity_blocking() {
+    SyncBoundedQueue::<()>::new(0);
+}
+
+#[test]
+fn timeouts() {
+    let q = SyncBoundedQueue::new(1);
+    assert!(matches!(
+        q.remove_timeout(Duration::from_millis(10)),
+        Err(QueueError::EmptyQueue)
+    ));
+    assert!(q.add(1).is_ok());
+    assert!(matches!(q.add(2), Err(QueueError::FullQueue)));
+
+    let start = Instant::now();
+    let result = q.add_timeout(2, Duration::from_millis(20));
+    assert_eq!(result, Err(2));
+    assert!(start.elapsed() >= Duration::from_millis(20));
+
+    assert!(matches!(q.remove_timeout(Duration::MAX), Ok(1)));
+    assert!(q.add_timeout(3, Duration::ZERO).is_ok());
+    assert_eq!(q.len(), 1);
+}
+
+#[test]
+fn add_timeout_hands_element_back() {
+    let q = SyncBoundedQueue::new(1);
+    assert!(q.add(String::from("first")).is_ok());
+
+    let result = q.add_timeout(String::from("second"), Duration::from_millis(10));
+    assert_eq!(result, Err(String::from("second")));
+    assert_eq!(q.len(), 1);
+    assert!(matches!(q.remove(), Ok(ref s) if s == "first"));
+}
+
+#[test]
+fn wakes_waiting_producer() {
+    let q = Arc::new(SyncBoundedQueue::new(1));
+    assert!(q.add(0).is_ok());
+
+    let producer = {
+        let q = Arc::clone(&q);
+        thread::spawn(move || q.add_timeout(1, Duration::from_secs(10)))
+    };
+    thread::sleep(Duration::from_millis(20));
+    assert!(matches!(q.remove(), Ok(0)));
+    assert!(producer.join().unwrap().is_ok());
+    assert!(matches!(q.remove(), Ok(1)));
+}
+
+#[test]
+fn producers_and_consumers() {
+    const PER_PRODUCER: usize = 1000;
+    let q = Arc::new(SyncBoundedQueue::new(4));
+
+    let producers: Vec<_> = (0..4)
+        .map(|p| {
+            let q = Arc::clone(&q);
+            thread::spawn(move || {
+                for n in 0..PER_PRODUCER {
+                    q.add_blocking(p * PER_PRODUCER + n);
+                }
+            })
+        })
+        .collect();
+    let consumers: Vec<_> = (0..2)
+        .map(|_| {
+            let q = Arc::clone(&q);
+            thread::spawn(move || {
+                let mut received = Vec::new();
+                while let Ok(n) = q.remove_timeout(Duration::from_millis(200)) {
+                    assert!(q.len() <= 4);
+                    received.push(n);
+                }
+                received
+            })
+        })
+        .collect();
+
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    let mut received: Vec<usize> = consumers
+        .into_iter()
+        .flat_map(|c| c.join().unwrap())
+        .collect();
+    received.sort();
+    assert!(received.into_iter().eq(0..4 * PER_PRODUCER));
+}
@@ -0,0 +1,6 @@
+mod binary_heap;
+mod bounded;
+mod mpmc;
+mod queue_trait;
+mod spsc;
+mod vec_deque;
@@ -0,0 +1,139 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use structures::queue::{MpmcQueue, Queue, QueueError};
+
+#[test]
+fn push_pop() {
+    let q = MpmcQueue::new(3);
+    assert_eq!(q.capacity(), 4);
+    assert!(matches!(q.remove(), Err(QueueError::EmptyQueue)));
+
+    for round in 0..5 {
+        for n in 0..4 {
+            assert!(q.add(round * 4 + n).is_ok());
+        }
+        assert!(matches!(q.add(99), Err(QueueError::FullQueue)));
+        assert_eq!(q.push(99), Err(99));
+        assert_eq!(q.len(), 4);
+        for n in 0..4 {
+            assert_eq!(q.pop(), Some(round * 4 + n));
+        }
+        assert!(q.is_empty());
+    }
+    assert_eq!(MpmcQueue::<()>::new(1).capacity(), 2);
+}
+
+#[test]
+#[should_panic]
+fn zero_capacity() {
+    MpmcQueue::<u32>::new(0);
+}
+
+#[test]
+fn drops_remaining() {
+    let value = Arc::new(());
+    let q = MpmcQueue::new(8);
+    for _ in 0..5 {
+        assert!(q.push(Arc::clone(&value)).is_ok());
+    }
+    drop(q.pop());
+    assert_eq!(Arc::strong_count(&value), 5);
+    drop(q);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn stress() {
+    const THREADS: usize = 4;
+    const PER_THREAD: usize = 20_000;
+    let q = Arc::new(MpmcQueue::new(32));
+    let received = Arc::new(AtomicUsize::new(0));
+
+    let producers: Vec<_> = (0..THREADS)
+        .map(|t| {
+            let q = Arc::clone(&q);
+            thread::spawn(move || {
+                for n in 0..PER_THREAD {
+                    let mut value = t * PER_THREAD + n;
+                    while let Err(returned) = q.push(value) {
+                        value = returned;
+                        thread::yield_now();
+                    }
+                }
+            })
+        })
+        .collect();
+    let consumers: Vec<_> = (0..THREADS)
+        .map(|_| {
+            let q = Arc::clone(&q);
+            let received = Arc::clone(&received);
+            thread::spawn(move || {
+                let mut seen = Vec::new();
+                while received.load(Ordering::Relaxed) < THREADS * PER_THREAD {
+                    match q.pop() {
+                        Some(n) => {
+                            received.fetch_add(1, Ordering::Relaxed);
+                            seen.push(n);
+                        }
+                        None => thread::yield_now(),
+                    }
+                }
+                seen
+            })
+        })
+        .collect();
+
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    let mut seen: Vec<usize> = consumers
+        .into_iter()
+        .flat_map(|c| c.join().unwrap())
+        .collect();
+    seen.sort();
+    assert!(seen.into_iter().eq(0..THREADS * PER_THREAD));
+    assert!(q.is_empty());
+}
+
+#[test]
+fn peek_and_exclusive_view() {
+    let mut q = MpmcQueue::new(4);
+    assert_eq!(q.peek(), None);
+    for n in 0..4 {
+        q.push(n).unwrap();
+    }
+    assert_eq!(q.pop(), Some(0));
+    q.push(4).unwrap();
+    assert_eq!(q.peek(), Some(&1));
+
+    let mut view = q.exclusive();
+    assert_eq!(view.len(), 4);
+    assert_eq!(view.capacity(), Some(4));
+    // the positions wrap around the end of the buffer
+    assert_eq!(view.iter().copied().collect::<Vec<_>>(), [1, 2, 3, 4]);
+    *view.peek_mut().unwrap() = 10;
+    assert_eq!(view.peek(), Some(&10));
+    assert_eq!(view.drain().collect::<Vec<_>>(), [10, 2, 3, 4]);
+    assert!(q.is_empty());
+}
+
+#[test]
+fn peek_after_concurrent_use() {
+    let mut q = MpmcQueue::new(64);
+    thread::scope(|s| {
+        for t in 0..4 {
+            let q = &q;
+            s.spawn(move || {
+                for n in 0..8 {
+                    q.push(t * 8 + n).unwrap();
+                }
+            });
+        }
+    });
+    // every push has finished once the threads are joined, so the view sees all of them
+    let mut seen: Vec<_> = q.exclusive().iter().copied().collect();
+    seen.sort();
+    assert_eq!(seen, (0..32).collect::<Vec<_>>());
+    assert!(q.peek().is_some());
+}
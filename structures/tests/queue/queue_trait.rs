@@ -0,0 +1,113 @@
+use structures::queue::{
+    BinaryHeap, BoundedQueue, MinHeap, MpmcQueue, Queue, QueueError, SpscQueue, VecDeque,
+};
+
+/// Exercises a queue through the trait only, the way generic scheduler code would.
+fn exercise<Q: Queue<u32>>(mut queue: Q, expected_order: [u32; 4]) {
+    assert!(queue.is_empty());
+    assert_eq!(queue.peek(), None);
+    assert!(queue.peek_mut().is_none());
+    assert_eq!(queue.remove(), Err(QueueError::EmptyQueue));
+
+    for n in [3, 1, 4, 2] {
+        queue.add(n).unwrap();
+    }
+    assert_eq!(queue.len(), 4);
+    assert_eq!(queue.iter().sum::<u32>(), 10);
+    assert_eq!(queue.peek(), Some(&expected_order[0]));
+
+    assert_eq!(queue.remove(), Ok(expected_order[0]));
+    let drained: Vec<u32> = queue.drain().collect();
+    assert_eq!(drained, expected_order[1..]);
+    assert!(queue.is_empty());
+
+    // the guard hands out the next element, and the queue still holds it afterwards
+    queue.add(5).unwrap();
+    *queue.peek_mut().unwrap() += 10;
+    assert_eq!(queue.remove(), Ok(15));
+}
+
+#[test]
+fn generic_queues() {
+    exercise(VecDeque::new(), [3, 1, 4, 2]);
+    exercise(BoundedQueue::new(4), [3, 1, 4, 2]);
+    exercise(BinaryHeap::new(), [4, 3, 2, 1]);
+    exercise(MinHeap::new(), [1, 2, 3, 4]);
+    exercise(SpscQueue::new(4), [3, 1, 4, 2]);
+    exercise(MpmcQueue::new(4).exclusive(), [3, 1, 4, 2]);
+    exercise(Box::new(BinaryHeap::new()), [4, 3, 2, 1]);
+    exercise(structures::pointer::Box::new(MinHeap::new()), [1, 2, 3, 4]);
+}
+
+/// A scheduler that only knows it has some queue of jobs: it queues a batch, then runs
+/// everything in the order the queue hands it out.
+fn schedule<Q: Queue<u32> + ?Sized>(queue: &mut Q, jobs: &[u32]) -> Vec<u32> {
+    for &job in jobs {
+        queue.add(job).unwrap();
+    }
+    let mut ran = Vec::new();
+    while let Ok(job) = queue.remove() {
+        ran.push(job);
+    }
+    ran
+}
+
+#[test]
+fn generic_over_borrowed_queues() {
+    let jobs = [3, 1, 4, 2];
+
+    let mut deque = VecDeque::new();
+    deque.add(0).unwrap();
+    assert_eq!(schedule(&mut deque, &jobs), [0, 3, 1, 4, 2]);
+    assert!(deque.is_empty());
+    // inherent methods still resolve through a borrow
+    let capacity = deque.capacity();
+    let borrowed = &mut deque;
+    assert_eq!(borrowed.capacity(), capacity);
+
+    assert_eq!(schedule(&mut BinaryHeap::new(), &jobs), [4, 3, 2, 1]);
+    assert_eq!(schedule(&mut BoundedQueue::new(4), &jobs), jobs);
+    assert_eq!(schedule(&mut SpscQueue::new(4), &jobs), jobs);
+    let mut mpmc = MpmcQueue::new(4);
+    assert_eq!(schedule(&mut mpmc.exclusive(), &jobs), jobs);
+
+    let mut boxed: Box<MinHeap<u32>> = Box::new(MinHeap::new());
+    assert_eq!(schedule(&mut boxed, &jobs), [1, 2, 3, 4]);
+    let mut boxed = structures::pointer::Box::new(BoundedQueue::new(4));
+    assert_eq!(schedule(&mut boxed, &jobs), jobs);
+}
+
+#[test]
+fn peek_mut_restores_order() {
+    let mut heap: BinaryHeap<u32> = [5, 7, 1].into_iter().collect();
+    *Queue::peek_mut(&mut heap).unwrap() = 0;
+    assert_eq!(Queue::peek(&heap), Some(&5));
+
+    let mut heap: MinHeap<u32> = [5, 7, 1].into_iter().collect();
+    *Queue::peek_mut(&mut heap).unwrap() = 9;
+    assert_eq!(Queue::peek(&heap), Some(&5));
+}
+
+#[test]
+fn capacity() {
+    assert_eq!(Queue::capacity(&BoundedQueue::<u32>::new(3)), Some(3));
+    assert_eq!(Queue::capacity(&VecDeque::<u32>::with_capacity(3)), None);
+}
+
+#[test]
+fn drain_dropped_early() {
+    let mut queue: VecDeque<u32> = (0..5).collect();
+    let mut drain = Queue::drain(&mut queue);
+    assert_eq!(drain.len(), 5);
+    assert_eq!(drain.next(), Some(0));
+    drop(drain);
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn error_display() {
+    assert_eq!(QueueError::EmptyQueue.to_string(), "queue is empty");
+    assert_eq!(QueueError::FullQueue.to_string(), "queue is full");
+    let err: Box<dyn std::error::Error> = Box::new(QueueError::FullQueue);
+    assert_eq!(format!("{err:?}"), "FullQueue");
+}
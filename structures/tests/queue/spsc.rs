@@ -0,0 +1,114 @@
+use std::rc::Rc;
+use std::thread;
+use structures::queue::spsc;
+use structures::queue::{Queue, QueueError, SpscQueue};
+
+#[test]
+fn push_pop() {
+    let (producer, mut consumer) = spsc::channel(3);
+    assert_eq!(producer.capacity(), 3);
+    assert!(consumer.pop().is_none());
+    assert!(matches!(consumer.remove(), Err(QueueError::EmptyQueue)));
+
+    for round in 0..5 {
+        for n in 0..3 {
+            assert!(producer.add(round * 3 + n).is_ok());
+        }
+        assert!(matches!(producer.add(99), Err(QueueError::FullQueue)));
+        assert_eq!(producer.push(99), Err(99));
+        assert_eq!(consumer.len(), 3);
+
+        assert_eq!(consumer.peek(), Some(&(round * 3)));
+        for n in 0..3 {
+            assert_eq!(consumer.pop(), Some(round * 3 + n));
+        }
+        assert!(consumer.is_empty());
+        assert_eq!(consumer.peek(), None);
+    }
+}
+
+#[test]
+fn drops_remaining() {
+    let value = Rc::new(());
+    let (producer, consumer) = spsc::channel(4);
+    for _ in 0..3 {
+        assert!(producer.push(Rc::clone(&value)).is_ok());
+    }
+    drop(consumer.pop());
+    drop(producer);
+    assert_eq!(Rc::strong_count(&value), 3);
+    drop(consumer);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn zero_sized() {
+    let (producer, consumer) = spsc::channel(2);
+    assert!(producer.push(()).is_ok());
+    assert!(producer.push(()).is_ok());
+    assert_eq!(producer.push(()), Err(()));
+    assert_eq!(consumer.pop(), Some(()));
+}
+
+#[test]
+fn stress() {
+    const COUNT: usize = 200_000;
+    let (producer, consumer) = spsc::channel(16);
+
+    let sender = thread::spawn(move || {
+        for n in 0..COUNT {
+            let mut value = n;
+            while let Err(returned) = producer.push(value) {
+                value = returned;
+                thread::yield_now();
+            }
+        }
+    });
+    let receiver = thread::spawn(move || {
+        let mut expected = 0;
+        while expected < COUNT {
+            match consumer.pop() {
+                Some(n) => {
+                    assert_eq!(n, expected);
+                    expected += 1;
+                }
+                None => thread::yield_now(),
+            }
+        }
+        assert!(consumer.pop().is_none());
+    });
+
+    sender.join().unwrap();
+    receiver.join().unwrap();
+}
+
+#[test]
+fn owning_queue() {
+    let mut queue = SpscQueue::new(3);
+    for n in 0..3 {
+        queue.add(n).unwrap();
+    }
+    assert_eq!(queue.add(3), Err(QueueError::FullQueue));
+    assert_eq!(queue.remove(), Ok(0));
+    queue.add(3).unwrap();
+
+    // the elements wrap around the end of the buffer
+    assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [1, 2, 3]);
+    assert_eq!(queue.iter().len(), 3);
+    assert_eq!(queue.peek(), Some(&1));
+    *queue.peek_mut().unwrap() = 10;
+
+    // splitting keeps the elements
+    let (producer, consumer) = queue.split();
+    let handle = thread::spawn(move || {
+        let mut received = Vec::new();
+        while received.len() < 4 {
+            if let Some(n) = consumer.pop() {
+                received.push(n);
+            }
+        }
+        received
+    });
+    while producer.push(4).is_err() {}
+    assert_eq!(handle.join().unwrap(), [10, 2, 3, 4]);
+}
@@ -0,0 +1,171 @@
+use std::cell::Cell;
+use structures::queue::{Queue, QueueError, VecDeque};
+use structures::vector::Vec;
+
+#[test]
+fn push_pop_both_ends() {
+    let mut d = VecDeque::new();
+    d.push_back(2);
+    d.push_back(3);
+    d.push_front(1);
+    d.push_front(0);
+    assert_eq!((d.len(), d.front(), d.back()), (4, Some(&0), Some(&3)));
+    assert_eq!(d.pop_front(), Some(0));
+    assert_eq!(d.pop_back(), Some(3));
+    assert_eq!(d.pop_back(), Some(2));
+    assert_eq!(d.pop_front(), Some(1));
+    assert_eq!((d.pop_front(), d.pop_back()), (None, None));
+}
+
+#[test]
+fn wrap_around_and_grow() {
+    let mut d = VecDeque::with_capacity(4);
+    let cap = d.capacity();
+    // move head towards the end, so that the elements wrap around
+    for n in 0..cap {
+        d.push_back(n);
+    }
+    d.pop_front();
+    d.pop_front();
+    d.push_back(cap);
+    d.push_back(cap + 1);
+    let (front, back) = d.as_slices();
+    assert!(!back.is_empty());
+    assert_eq!(front.len() + back.len(), cap);
+
+    // growing keeps the order, whichever way the elements get moved
+    for n in cap + 2..cap + 40 {
+        d.push_back(n);
+        d.push_front(0);
+        d.pop_front();
+    }
+    assert!(d.iter().copied().eq(2..cap + 40));
+    for (i, n) in (2..cap + 40).enumerate() {
+        assert_eq!(d[i], n);
+    }
+}
+
+#[test]
+fn make_contiguous_cases() {
+    // exercise every layout of wrapped elements and free space
+    for cap in [8, 12] {
+        for head_len in 1..cap {
+            for len in head_len + 1..=cap {
+                let mut d = VecDeque::with_capacity(cap);
+                let cap = d.capacity();
+                for _ in 0..cap - head_len {
+                    d.push_back(0);
+                    d.pop_front();
+                }
+                for n in 0..len {
+                    d.push_back(n);
+                }
+                assert_eq!(d.capacity(), cap);
+                let expected: std::vec::Vec<usize> = (0..len).collect();
+                assert_eq!(d.make_contiguous(), &expected[..]);
+                assert_eq!(d.as_slices(), (&expected[..], &[][..]));
+            }
+        }
+    }
+}
+
+#[test]
+fn iterators() {
+    let mut d: VecDeque<i32> = (1..=3).collect();
+    d.push_front(0);
+    assert!(d.iter().rev().copied().eq([3, 2, 1, 0]));
+
+    let mut iter = d.iter();
+    assert_eq!(
+        (iter.next(), iter.next_back(), iter.len()),
+        (Some(&0), Some(&3), 2)
+    );
+
+    for n in d.iter_mut() {
+        *n *= 10;
+    }
+    for n in &mut d {
+        *n += 1;
+    }
+    assert_eq!(d.iter().sum::<i32>(), 64);
+
+    let mut into_iter = d.clone().into_iter();
+    assert_eq!(into_iter.next_back(), Some(31));
+    assert!(into_iter.eq([1, 11, 21]));
+    assert_eq!(format!("{:?}", d), "[1, 11, 21, 31]");
+}
+
+#[test]
+fn insert_remove() {
+    let mut d: VecDeque<i32> = (0..6).collect();
+    d.pop_front();
+    d.push_back(6);
+    d.insert(0, -1);
+    d.insert(3, 100);
+    d.insert(d.len(), 7);
+    assert!(d.iter().copied().eq([-1, 1, 2, 100, 3, 4, 5, 6, 7]));
+    assert_eq!(d.remove(3), Some(100));
+    assert_eq!(d.remove(0), Some(-1));
+    assert_eq!(d.remove(6), Some(7));
+    assert_eq!(d.remove(6), None);
+    assert!(d.iter().copied().eq(1..7));
+    d.swap(0, 5);
+    assert_eq!((d[0], d[5]), (6, 1));
+}
+
+#[test]
+fn queue_trait() {
+    let mut d = VecDeque::new();
+    assert!(d.add("a").is_ok());
+    assert!(d.add("b").is_ok());
+    assert_eq!(Queue::peek(&d), Some(&"a"));
+    // the inherent remove takes an index, so the trait method is called explicitly
+    assert!(matches!(Queue::remove(&mut d), Ok("a")));
+    assert!(matches!(Queue::remove(&mut d), Ok("b")));
+    assert!(matches!(Queue::remove(&mut d), Err(QueueError::EmptyQueue)));
+}
+
+#[test]
+fn vec_conversions() {
+    let v: Vec<i32> = (0..5).collect();
+    let ptr = v.as_ptr();
+    let mut d = VecDeque::from(v);
+    d.pop_front();
+    d.push_back(5);
+    let v = Vec::from(d);
+    assert_eq!((v.as_ptr(), &v[..]), (ptr, &[1, 2, 3, 4, 5][..]));
+}
+
+#[test]
+fn drops_and_zst() {
+    struct Counted<'a>(&'a Cell<usize>);
+
+    impl Drop for Counted<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let drops = Cell::new(0);
+    let mut d = VecDeque::new();
+    for _ in 0..10 {
+        d.push_front(Counted(&drops));
+    }
+    d.truncate(7);
+    assert_eq!(drops.get(), 3);
+    d.pop_back();
+    assert_eq!(drops.get(), 4);
+    drop(d);
+    assert_eq!(drops.get(), 10);
+
+    let mut d = VecDeque::new();
+    for _ in 0..100 {
+        d.push_front(());
+        d.push_back(());
+    }
+    d.pop_front();
+    assert_eq!((d.len(), d.capacity()), (199, usize::MAX));
+    assert_eq!(d.make_contiguous().len(), 199);
+    d.clear();
+    assert!(d.is_empty());
+}
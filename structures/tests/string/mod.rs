@@ -0,0 +1,4 @@
+mod owned;
+mod rope;
+mod shared_str;
+mod small_string;
@@ -0,0 +1,125 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use structures::string::String;
+use structures::vector::Vec;
+
+#[test]
+fn push_and_pop() {
+    let mut s = String::new();
+    s.push_str("héllo");
+    s.push(' ');
+    s.push('世');
+    s.push('🦀');
+    assert_eq!(s, "héllo 世🦀");
+    assert_eq!(s.len(), 14);
+
+    assert_eq!(s.pop(), Some('🦀'));
+    assert_eq!(s.pop(), Some('世'));
+    assert_eq!(s, "héllo ");
+    assert_eq!(String::new().pop(), None);
+}
+
+#[test]
+fn insert_and_remove() {
+    let mut s = String::from("héllo");
+    s.insert(0, 'ø');
+    s.insert(s.len(), '!');
+    s.insert_str(3, "XY");
+    assert_eq!(s, "øhXYéllo!");
+
+    assert_eq!(s.remove(3), 'X');
+    assert_eq!(s.remove(4), 'é');
+    assert_eq!(s.remove(0), 'ø');
+    assert_eq!(s, "hYllo!");
+}
+
+#[test]
+#[should_panic]
+fn insert_inside_char() {
+    let mut s = String::from("é");
+    s.insert(1, 'a');
+}
+
+#[test]
+#[should_panic]
+fn remove_at_end() {
+    let mut s = String::from("ab");
+    s.remove(2);
+}
+
+#[test]
+fn truncate() {
+    let mut s = String::from("añb");
+    s.truncate(10);
+    assert_eq!(s, "añb");
+    s.truncate(3);
+    assert_eq!(s, "añ");
+    s.truncate(0);
+    assert!(s.is_empty());
+}
+
+#[test]
+#[should_panic]
+fn truncate_inside_char() {
+    let mut s = String::from("añb");
+    s.truncate(2);
+}
+
+#[test]
+fn from_utf8() {
+    let s = String::from_utf8(Vec::from("ok ✓")).unwrap();
+    assert_eq!(s, "ok ✓");
+
+    let err = String::from_utf8(Vec::from(&[b'a', 0xff, b'b'][..])).unwrap_err();
+    assert_eq!(err.utf8_error().valid_up_to(), 1);
+    assert_eq!(err.as_bytes(), [b'a', 0xff, b'b']);
+    assert_eq!(
+        err.to_string(),
+        "invalid utf-8 sequence of 1 bytes from index 1"
+    );
+    assert_eq!(err.into_bytes(), [b'a', 0xff, b'b']);
+}
+
+#[test]
+fn from_utf8_lossy() {
+    assert_eq!(String::from_utf8_lossy(b"plain"), "plain");
+    assert_eq!(
+        String::from_utf8_lossy(b"a\xffb\xe2\x82c"),
+        "a\u{FFFD}b\u{FFFD}c"
+    );
+}
+
+#[test]
+fn str_methods_and_formatting() {
+    let mut s = String::new();
+    let id = "id";
+    write!(s, "{id}-{:02}", 7).unwrap();
+    assert_eq!(s, "id-07");
+    assert!(s.starts_with("id"));
+    assert_eq!(&s[3..], "07");
+    s.make_ascii_uppercase();
+
+    assert_eq!(format!("{s}"), "ID-07");
+    assert_eq!(format!("{s:?}"), "\"ID-07\"");
+    assert_eq!(format!("{s:>6}"), " ID-07");
+}
+
+#[test]
+fn conversions_and_traits() {
+    let s: String = "abc".chars().rev().collect();
+    assert_eq!(s, "cba");
+    let s = s + "d";
+    assert_eq!(std::string::String::from(s.clone()), "cbad");
+    assert_eq!(String::from(std::string::String::from("xy")), "xy");
+    assert_eq!("q".parse::<String>().unwrap(), "q");
+
+    let mut tail = String::from("hello world");
+    let world = tail.split_off(6);
+    assert_eq!((tail.as_str(), world.as_str()), ("hello ", "world"));
+
+    // hashing matches str, so lookups by &str work
+    let set: HashSet<String> = ["a", "b"].iter().map(|&s| String::from(s)).collect();
+    assert!(set.contains("a"));
+    let (a, b) = (String::from("a"), String::from("b"));
+    assert!(a < b);
+}
@@ -0,0 +1,141 @@
+use structures::string::{Rope, String};
+
+use crate::common::xorshift;
+
+/// Picks a char boundary of `text` at random.
+fn boundary(text: &str, seed: &mut u32) -> usize {
+    let mut idx = xorshift(seed) as usize % (text.len() + 1);
+    while !text.is_char_boundary(idx) {
+        idx -= 1;
+    }
+    idx
+}
+
+#[test]
+fn matches_std_string() {
+    let pieces = [
+        "a",
+        "é",
+        "日本語",
+        "\n",
+        "line\nbreak",
+        "🦀🦀",
+        &"x".repeat(700),
+    ];
+    let mut seed = 0xdead_beef;
+    let mut rope = Rope::new();
+    let mut model = std::string::String::new();
+
+    for _ in 0..2000 {
+        if xorshift(&mut seed).is_multiple_of(3) && !model.is_empty() {
+            let mut a = boundary(&model, &mut seed);
+            let mut b = boundary(&model, &mut seed);
+            if a > b {
+                std::mem::swap(&mut a, &mut b);
+            }
+            rope.remove(a..b);
+            model.replace_range(a..b, "");
+        } else {
+            let piece = pieces[xorshift(&mut seed) as usize % pieces.len()];
+            let at = boundary(&model, &mut seed);
+            rope.insert(at, piece);
+            model.insert_str(at, piece);
+        }
+
+        assert_eq!(rope.len_bytes(), model.len());
+        assert_eq!(rope.len_chars(), model.chars().count());
+        assert_eq!(rope.len_lines(), model.matches('\n').count() + 1);
+    }
+    assert_eq!(rope, model.as_str());
+    assert!(rope.chunks().count() > 1);
+}
+
+#[test]
+fn char_offsets() {
+    let mut rope = Rope::from("añb日c");
+    assert_eq!(rope.char_to_byte(2), 3);
+    assert_eq!(rope.char_to_byte(5), rope.len_bytes());
+    assert_eq!(rope.byte_to_char(4), 3);
+    assert_eq!(rope.byte_to_char(5), 3);
+
+    rope.insert_at_char(3, "🦀");
+    assert_eq!(rope, "añb🦀日c");
+    rope.remove_chars(1..4);
+    assert_eq!(rope, "a日c");
+}
+
+#[test]
+fn char_offsets_across_leaves() {
+    let text: std::string::String = (0..3000)
+        .map(|i| if i % 3 == 0 { 'é' } else { 'a' })
+        .collect();
+    let rope = Rope::from(text.as_str());
+    for (char_idx, (byte_idx, _)) in text.char_indices().enumerate().step_by(97) {
+        assert_eq!(rope.char_to_byte(char_idx), byte_idx);
+        assert_eq!(rope.byte_to_char(byte_idx), char_idx);
+    }
+}
+
+#[test]
+#[should_panic]
+fn insert_inside_char() {
+    let mut rope = Rope::from("é");
+    rope.insert(1, "x");
+}
+
+#[test]
+fn lines() {
+    let text: std::string::String = (0..500).map(|i| format!("line {i}\n")).collect();
+    let rope = Rope::from(text.as_str());
+    assert_eq!(rope.len_lines(), 501);
+
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        let start = rope.line_to_byte(i);
+        assert_eq!(&text[start..start + line.len()], line);
+        assert_eq!(rope.byte_to_line(start), i);
+        assert_eq!(rope.line(i).collect::<std::string::String>(), line);
+    }
+    assert_eq!(rope.line(500).count(), 0);
+    assert_eq!(rope.byte_to_line(rope.len_bytes()), 500);
+}
+
+#[test]
+fn chunks_in_range() {
+    let text = "0123456789".repeat(200);
+    let rope = Rope::from(text.as_str());
+    let slice: std::string::String = rope.chunks_in(505..1520).collect();
+    assert_eq!(slice, text[505..1520]);
+    assert_eq!(rope.chunks_in(7..7).count(), 0);
+}
+
+#[test]
+fn conversions_split_and_append() {
+    let text = "αβγ ".repeat(400);
+    let mut rope = Rope::from(String::from(text.as_str()));
+    assert_eq!(String::from(&rope), text.as_str());
+    assert_eq!(rope.to_string(), text);
+
+    let tail = rope.split_off(1400);
+    assert_eq!(rope, &text[..1400]);
+    assert_eq!(tail, &text[1400..]);
+
+    rope.append(tail);
+    assert_eq!(rope, text.as_str());
+    assert_eq!(rope, Rope::from(text.as_str()));
+}
+
+#[test]
+fn many_small_edits() {
+    // typing one char at a time must not leave behind a leaf per keystroke
+    let mut rope = Rope::new();
+    let mut seed = 1;
+    for i in 0..20_000 {
+        let at = xorshift(&mut seed) as usize % (rope.len_bytes() + 1);
+        rope.insert(at, if i % 40 == 0 { "\n" } else { "k" });
+    }
+    assert_eq!(rope.len_bytes(), 20_000);
+    assert!(rope.chunks().count() < 20_000 / 64);
+
+    rope.remove(100..19_900);
+    assert_eq!(rope.len_bytes(), 200);
+}
@@ -0,0 +1,69 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::thread;
+use structures::string::{SharedStr, SmallString, String};
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn clones_share() {
+    let a = SharedStr::from("identifier");
+    let b = a.clone();
+    assert!(SharedStr::ptr_eq(&a, &b));
+    assert_eq!(SharedStr::strong_count(&a), 2);
+    assert_eq!(b.len(), 10);
+    drop(b);
+    assert_eq!(SharedStr::strong_count(&a), 1);
+
+    let c = SharedStr::from("identifier");
+    assert!(!SharedStr::ptr_eq(&a, &c));
+    assert_eq!(a, c);
+}
+
+#[test]
+fn into_string() {
+    let s = SharedStr::from(String::from("owned"));
+    let other = s.clone();
+    assert_eq!(s.into_string(), "owned");
+    assert_eq!(String::from(other), "owned");
+    assert_eq!(SharedStr::from(SmallString::from("small")), "small");
+    assert!(SharedStr::default().is_empty());
+}
+
+#[test]
+fn agrees_with_str() {
+    let mut map = HashMap::new();
+    map.insert(SharedStr::from("key"), 1);
+    assert_eq!(map.get("key"), Some(&1));
+    assert_eq!(hash(&SharedStr::from("key")), hash("key"));
+
+    let a = SharedStr::from("a");
+    let b = SharedStr::from("b");
+    assert!(a < b);
+    assert_eq!(format!("{a} {b:?}"), "a \"b\"");
+}
+
+#[test]
+fn across_threads() {
+    let s = SharedStr::from("shared between threads");
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let s = s.clone();
+            thread::spawn(move || {
+                for _ in 0..1000 {
+                    drop(s.clone());
+                }
+                s.len()
+            })
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), s.len());
+    }
+    assert_eq!(SharedStr::strong_count(&s), 1);
+}
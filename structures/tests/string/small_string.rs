@@ -0,0 +1,97 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use structures::string::smallstring::INLINE_CAP;
+use structures::string::{SmallString, String};
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[test]
+fn inline_until_full() {
+    assert_eq!(mem::size_of::<SmallString>(), mem::size_of::<Vec<u8>>());
+    assert_eq!(INLINE_CAP, mem::size_of::<usize>() * 3 - 1);
+
+    let exact = "a".repeat(INLINE_CAP);
+    let mut s = SmallString::from(exact.as_str());
+    assert!(!s.spilled());
+    assert_eq!(s.capacity(), INLINE_CAP);
+    assert_eq!(s, exact.as_str());
+
+    s.push('é');
+    assert!(s.spilled());
+    assert_eq!(s.len(), INLINE_CAP + 2);
+    assert_eq!(s.pop(), Some('é'));
+    assert_eq!(s, exact.as_str());
+
+    s.shrink_to_fit();
+    assert!(!s.spilled());
+    assert_eq!(s, exact.as_str());
+}
+
+#[test]
+fn push_pop_truncate() {
+    let mut s = SmallString::new();
+    for word in ["short", " and ", "then much longer than inline"] {
+        s.push_str(word);
+    }
+    assert_eq!(s, "short and then much longer than inline");
+
+    s.truncate(5);
+    assert_eq!(s, "short");
+    assert!(s.spilled());
+    s.clear();
+    assert!(s.is_empty());
+    assert_eq!(s.pop(), None);
+
+    let mut s = SmallString::from("日本");
+    s.truncate(3);
+    assert_eq!(s, "日");
+}
+
+#[test]
+#[should_panic]
+fn truncate_inside_char() {
+    let mut s = SmallString::from("日本");
+    s.truncate(1);
+}
+
+#[test]
+fn conversions() {
+    let long = String::from("this one is definitely longer than twenty-three bytes");
+    let small = SmallString::from(long.clone());
+    assert!(small.spilled());
+    assert_eq!(small.clone().into_string(), long);
+
+    let short = SmallString::from(String::from("tiny"));
+    assert!(!short.spilled());
+    assert_eq!(String::from(short), "tiny");
+
+    let collected: SmallString = ["ab", "cd"].into_iter().collect();
+    assert_eq!(collected.as_str(), "abcd");
+    assert_eq!("x".parse::<SmallString>().unwrap(), "x");
+}
+
+#[test]
+fn agrees_with_str() {
+    let words = [
+        "pear",
+        "apple",
+        "a much longer identifier that spills",
+        "apple",
+    ];
+    let mut small: Vec<SmallString> = words.iter().map(|&w| SmallString::from(w)).collect();
+    let mut expected = words.to_vec();
+    small.sort();
+    expected.sort();
+    assert!(small.iter().map(|s| s.as_str()).eq(expected));
+
+    for w in words {
+        assert_eq!(hash(&SmallString::from(w)), hash(w));
+    }
+    assert_eq!(SmallString::from("apple"), SmallString::from("apple"));
+    assert_eq!(format!("{:?}", SmallString::from("q\"")), "\"q\\\"\"");
+}
@@ -0,0 +1,66 @@
+use std::rc::Rc;
+use structures::vector::{ArrayVec, TryReserveError};
+
+#[test]
+fn push_until_full() {
+    let mut v = ArrayVec::<usize, 3>::new();
+    for num in 0..3 {
+        assert!(v.push(num).is_ok());
+    }
+    assert!(v.is_full());
+    let err = v.push(3).unwrap_err();
+    assert_eq!(err.to_string(), "insufficient capacity");
+    assert_eq!(err.element(), 3);
+    assert_eq!(&v[..], &[0, 1, 2]);
+}
+
+#[test]
+fn insert_remove() {
+    let mut v = ArrayVec::<i32, 3>::new();
+    v.insert(0, 2).unwrap();
+    v.insert(0, 0).unwrap();
+    v.insert(1, 1).unwrap();
+    assert_eq!(v.insert(0, -1).unwrap_err().element(), -1);
+    assert_eq!(&v[..], &[0, 1, 2]);
+    assert_eq!(v.remove(1), 1);
+    assert_eq!(v.swap_remove(0), 0);
+    assert_eq!(v.pop(), Some(2));
+    assert_eq!(v.pop(), None);
+}
+
+#[test]
+fn try_reserve() {
+    let mut v = ArrayVec::<u8, 4>::new();
+    v.push(0).unwrap();
+    assert!(v.try_reserve(3).is_ok());
+    assert!(matches!(v.try_reserve(4), Err(TryReserveError::CapacityOverflow)));
+    assert!(matches!(v.try_reserve_exact(usize::MAX), Err(TryReserveError::CapacityOverflow)));
+}
+
+#[test]
+#[should_panic]
+fn extend_overflow() {
+    let mut v = ArrayVec::<u8, 4>::new();
+    v.extend(0..5);
+}
+
+#[test]
+fn into_inner() {
+    let mut v: ArrayVec<u8, 3> = (0..2).collect();
+    v = v.into_inner().unwrap_err();
+    v.push(2).unwrap();
+    assert_eq!(v.clone().into_inner().ok(), Some([0, 1, 2]));
+    assert_eq!(ArrayVec::from([4, 5]).iter().sum::<i32>(), 9);
+}
+
+#[test]
+fn into_iter_drops_rest() {
+    let rc = Rc::new(());
+    let v: ArrayVec<Rc<()>, 5> = (0..5).map(|_| Rc::clone(&rc)).collect();
+    let mut it = v.into_iter();
+    drop(it.next());
+    drop(it.next_back());
+    assert_eq!((it.len(), Rc::strong_count(&rc)), (3, 4));
+    drop(it);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
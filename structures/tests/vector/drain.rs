@@ -0,0 +1,58 @@
+use std::mem;
+use structures::vector::Vec;
+
+use crate::common::{DropCounter, Drops};
+
+#[test]
+fn drain_range() {
+    let mut v: Vec<usize> = (0..10).collect();
+    let drained: Vec<usize> = v.drain(2..5).collect();
+    assert_eq!(&drained[..], &[2, 3, 4]);
+    assert_eq!(&v[..], &[0, 1, 5, 6, 7, 8, 9]);
+
+    let drained: Vec<usize> = v.drain(..).rev().collect();
+    assert_eq!(&drained[..], &[9, 8, 7, 6, 5, 1, 0]);
+    assert_eq!(v.len(), 0);
+}
+
+#[test]
+fn drain_dropped_halfway() {
+    let drops = Drops::new();
+    let mut v: Vec<_> = Vec::new();
+    for _ in 0..6 {
+        v.push(DropCounter::new(&drops));
+    }
+    {
+        let mut drain = v.drain(1..5);
+        drop(drain.next());
+        assert_eq!(drops.get(), 1);
+    }
+    assert_eq!((drops.get(), v.len()), (4, 2));
+    drop(v);
+    assert_eq!(drops.get(), 6);
+}
+
+#[test]
+fn drain_leaked() {
+    let mut v: Vec<usize> = (0..6).collect();
+    mem::forget(v.drain(2..4));
+    // the drained range and the tail are leaked, but the head is still valid
+    assert_eq!(&v[..], &[0, 1]);
+}
+
+#[test]
+fn drain_zst() {
+    let mut v: Vec<_> = Vec::new();
+    for _ in 0..10 {
+        v.push(());
+    }
+    assert_eq!(v.drain(3..7).count(), 4);
+    assert_eq!(v.len(), 6);
+}
+
+#[test]
+#[should_panic]
+fn drain_out_of_bounds() {
+    let mut v: Vec<usize> = (0..3).collect();
+    v.drain(2..5);
+}
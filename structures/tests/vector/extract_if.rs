@@ -0,0 +1,46 @@
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use structures::vector::Vec;
+
+#[test]
+fn extract_even() {
+    let mut v: Vec<usize> = (0..10).collect();
+    let even: Vec<usize> = v.extract_if(|x| *x % 2 == 0).collect();
+    assert_eq!(&even[..], &[0, 2, 4, 6, 8]);
+    assert_eq!(&v[..], &[1, 3, 5, 7, 9]);
+}
+
+#[test]
+fn extract_dropped_halfway() {
+    let mut v: Vec<usize> = (0..10).collect();
+    {
+        let mut extract = v.extract_if(|x| *x % 3 == 0);
+        assert_eq!(extract.next(), Some(0));
+        assert_eq!(extract.next(), Some(3));
+    }
+    // uninspected elements are kept
+    assert_eq!(&v[..], &[1, 2, 4, 5, 6, 7, 8, 9]);
+}
+
+#[test]
+fn extract_leaked() {
+    let mut v: Vec<usize> = (0..10).collect();
+    let mut extract = v.extract_if(|_| true);
+    extract.next();
+    mem::forget(extract);
+    assert_eq!(v.len(), 0);
+}
+
+#[test]
+fn extract_pred_panics() {
+    let mut v: Vec<String> = (0..6).map(|x| x.to_string()).collect();
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        v.extract_if(|s| match s.as_str() {
+            "4" => panic!("predicate panicked"),
+            s => s == "1" || s == "2",
+        })
+        .for_each(drop);
+    }));
+    assert!(result.is_err());
+    assert_eq!(&v[..], &["0", "3", "4", "5"]);
+}
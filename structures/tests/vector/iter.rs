@@ -19,3 +19,97 @@ fn test_into_iter_clone() {
     assert_eq!(it.next(), None);
 }
 
+#[test]
+fn test_into_iter_default() {
+    let mut it: IntoIter<i32> = Default::default();
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+}
+
+#[test]
+fn test_iter_both_ends() {
+    let v: Vec<i32> = (1..=5).collect();
+    let mut it = v.iter();
+    assert_eq!(it.len(), 5);
+    assert_eq!(it.next(), Some(&1));
+    assert_eq!(it.next_back(), Some(&5));
+    assert_eq!(it.as_slice(), &[2, 3, 4]);
+    assert_eq!(it.clone().rev().copied().collect::<Vec<_>>()[..], [4, 3, 2]);
+    assert_eq!(it.len(), 3);
+    assert_eq!(it.by_ref().count(), 3);
+    assert_eq!(it.next(), None);
+    assert_eq!(it.next_back(), None);
+}
+
+#[test]
+fn test_iter_for_loop() {
+    let mut v: Vec<i32> = (1..=4).collect();
+    for x in &mut v {
+        *x *= 10;
+    }
+    let mut sum = 0;
+    for x in &v {
+        sum += x;
+    }
+    assert_eq!(sum, 100);
+
+    let mut it = v.iter_mut();
+    *it.next_back().unwrap() = 0;
+    assert_eq!(it.into_slice(), &[10, 20, 30]);
+    assert_eq!(&v[..], &[10, 20, 30, 0]);
+}
+
+#[test]
+fn test_iter_zst() {
+    let mut v: Vec<_> = Vec::new();
+    for _ in 0..10 {
+        v.push(());
+    }
+    let mut it = v.iter();
+    assert_eq!(it.len(), 10);
+    assert_eq!(it.next(), Some(&()));
+    assert_eq!(it.next_back(), Some(&()));
+    assert_eq!((it.len(), it.as_slice().len()), (8, 8));
+    assert_eq!(v.iter_mut().rev().count(), 10);
+    assert_eq!((&v).into_iter().fuse().count(), 10);
+}
+
+#[test]
+fn test_vec_into_iter_clone() {
+    let v: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    let mut it = v.into_iter();
+    assert_eq!(it.next().as_deref(), Some("a"));
+    let cloned = it.clone();
+    assert_eq!(it.next_back().as_deref(), Some("c"));
+    assert_eq!(it.as_slice(), &["b"]);
+    assert_eq!(cloned.len(), 2);
+    assert_eq!(cloned.collect::<Vec<_>>()[..], ["b", "c"]);
+}
+
+#[test]
+fn test_into_iter_drops_rest() {
+    use std::rc::Rc;
+
+    let rc = Rc::new(());
+    let v: Vec<Rc<()>> = (0..5).map(|_| Rc::clone(&rc)).collect();
+    let mut it = v.into_iter();
+    drop(it.next());
+    drop(it.next_back());
+    assert_eq!(Rc::strong_count(&rc), 4);
+    drop(it);
+    assert_eq!(Rc::strong_count(&rc), 1);
+}
+
+#[test]
+fn test_into_iter_zst() {
+    let mut v: Vec<_> = Vec::new();
+    for _ in 0..5 {
+        v.push(());
+    }
+    let mut it = v.into_iter();
+    assert_eq!(it.len(), 5);
+    assert_eq!(it.next_back(), Some(()));
+    assert_eq!(it.next(), Some(()));
+    assert_eq!(it.size_hint(), (3, Some(3)));
+    assert_eq!(it.rev().count(), 3);
+}
@@ -1,3 +1,9 @@
+mod array_vec;
+mod drain;
+mod extract_if;
 mod iter;
 mod raw_vec;
+mod small_vec;
+mod sort;
+mod splice;
 mod vec;
@@ -1,4 +1,25 @@
-use structures::vector::RawVec;
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::cell::Cell;
+use std::ptr::{self, NonNull};
+use structures::vector::{FixedIncrement, GrowthPolicy, OneAndHalf, RawPartsError, RawVec, Vec};
+
+/// Forwards to `Global` and counts live allocations.
+#[derive(Default)]
+struct CountingAlloc {
+    live: Cell<isize>,
+}
+
+unsafe impl Allocator for &CountingAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        self.live.set(self.live.get() + 1);
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        self.live.set(self.live.get() - 1);
+        Global.deallocate(ptr, layout)
+    }
+}
 
 #[test]
 fn reserve_push() {
@@ -23,3 +44,118 @@ fn reserve_exact() {
     rv.reserve_exact(11, 30);
     assert_eq!(rv.capacity(), 41);
 }
+
+#[test]
+fn custom_allocator() {
+    let counter = CountingAlloc::default();
+    {
+        let mut rv = RawVec::<usize, _>::new_in(&counter);
+        assert_eq!(counter.live.get(), 0);
+        rv.reserve_for_push(0);
+        assert_eq!(counter.live.get(), 1);
+        rv.reserve_for_push(4);
+        assert_eq!((rv.capacity(), counter.live.get()), (8, 1));
+    }
+    assert_eq!(counter.live.get(), 0);
+}
+
+#[test]
+fn growth_policies() {
+    let mut rv = RawVec::<usize, Global, OneAndHalf>::new();
+    let mut caps = [0; 4];
+    for cap in caps.iter_mut() {
+        rv.reserve_for_push(rv.capacity());
+        *cap = rv.capacity();
+    }
+    assert_eq!(caps, [4, 6, 9, 13]);
+
+    let mut rv = RawVec::<u8, Global, FixedIncrement<10>>::new();
+    for cap in caps.iter_mut() {
+        rv.reserve_for_push(rv.capacity());
+        *cap = rv.capacity();
+    }
+    assert_eq!(caps, [10, 20, 30, 40]);
+    rv.reserve(40, 25);
+    assert_eq!(rv.capacity(), 65);
+}
+
+#[test]
+fn custom_growth_policy() {
+    struct Exact;
+
+    impl GrowthPolicy for Exact {
+        fn grow(_cap: usize, required: usize) -> usize {
+            required
+        }
+
+        fn min_non_zero_cap(_elem_size: usize) -> usize {
+            1
+        }
+    }
+
+    let mut rv = RawVec::<u64, Global, Exact>::new();
+    rv.reserve_for_push(0);
+    assert_eq!(rv.capacity(), 1);
+    rv.reserve(1, 6);
+    assert_eq!(rv.capacity(), 7);
+    assert!(rv.try_reserve(7, usize::MAX).is_err());
+}
+
+#[test]
+fn shrink_to() {
+    let counter = CountingAlloc::default();
+    let mut rv = RawVec::<u32, _>::with_capacity_in(16, &counter);
+    rv.shrink_to(5);
+    assert_eq!((rv.capacity(), counter.live.get()), (5, 1));
+    rv.shrink_to(0);
+    assert_eq!((rv.capacity(), counter.live.get()), (0, 0));
+    rv.shrink_to(0);
+    rv.reserve_for_push(0);
+    assert_eq!((rv.capacity(), counter.live.get()), (4, 1));
+}
+
+#[test]
+#[should_panic]
+fn shrink_to_larger() {
+    let mut rv = RawVec::<u32>::with_capacity(4);
+    rv.shrink_to(5);
+}
+
+#[test]
+fn check_raw_parts() {
+    let mut v = Vec::<u32>::with_capacity(4);
+    let ptr = v.as_mut_ptr();
+    assert_eq!(Vec::<u32>::check_raw_parts(ptr, 4, 4), Ok(()));
+    assert_eq!(
+        Vec::<u32>::check_raw_parts(ptr, 5, 4),
+        Err(RawPartsError::LengthExceedsCapacity {
+            len: 5,
+            capacity: 4
+        })
+    );
+    assert_eq!(
+        RawVec::<u32>::check_raw_parts(ptr::null(), 0),
+        Err(RawPartsError::Null)
+    );
+    assert_eq!(
+        RawVec::<u32>::check_raw_parts(ptr.wrapping_byte_add(1), 4),
+        Err(RawPartsError::Misaligned { align: 4 })
+    );
+    assert_eq!(
+        RawVec::<u32>::check_raw_parts(ptr, usize::MAX / 2),
+        Err(RawPartsError::LayoutOverflow {
+            capacity: usize::MAX / 2
+        })
+    );
+    assert_eq!(
+        RawVec::<()>::check_raw_parts(ptr::dangling(), usize::MAX),
+        Ok(())
+    );
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "invalid raw parts: pointer is null")]
+fn from_raw_parts_null() {
+    let _ = unsafe { RawVec::<u32>::from_raw_parts(ptr::null_mut(), 0) };
+}
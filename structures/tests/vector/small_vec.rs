@@ -0,0 +1,82 @@
+use std::rc::Rc;
+use structures::vector::SmallVec;
+
+#[test]
+fn inline_then_spill() {
+    let mut v = SmallVec::<usize, 4>::new();
+    for num in 0..4 {
+        v.push(num);
+    }
+    assert_eq!((v.capacity(), v.spilled()), (4, false));
+    v.push(4);
+    assert_eq!((v.capacity(), v.spilled()), (8, true));
+    assert_eq!(&v[..], &[0, 1, 2, 3, 4]);
+    for num in 5..100 {
+        v.push(num);
+    }
+    assert_eq!(v.len(), 100);
+    assert_eq!(v.iter().sum::<usize>(), 4950);
+}
+
+#[test]
+fn insert_remove() {
+    let mut v = SmallVec::<i32, 2>::new();
+    v.insert(0, 2);
+    v.insert(0, 0);
+    v.insert(1, 1);
+    assert!(v.spilled());
+    assert_eq!(&v[..], &[0, 1, 2]);
+    assert_eq!(v.remove(1), 1);
+    assert_eq!(v.swap_remove(0), 0);
+    assert_eq!(v.pop(), Some(2));
+    assert_eq!(v.pop(), None);
+}
+
+#[test]
+fn extend_and_collect() {
+    let mut v: SmallVec<u8, 8> = (0..3).collect();
+    v.extend(3..6);
+    assert!(!v.spilled());
+    v.extend(vec![6, 7, 8]);
+    assert!(v.spilled());
+    assert_eq!(&v[..], &[0, 1, 2, 3, 4, 5, 6, 7, 8]);
+
+    let vec = v.clone().into_vec();
+    assert_eq!(&vec[..], &v[..]);
+}
+
+#[test]
+fn into_iter() {
+    let v: SmallVec<String, 2> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    let mut it = v.into_iter();
+    assert_eq!(it.next().as_deref(), Some("a"));
+    assert_eq!(it.next_back().as_deref(), Some("c"));
+    assert_eq!(it.len(), 1);
+    assert_eq!(it.as_slice(), &["b"]);
+}
+
+#[test]
+fn drops_elements() {
+    let rc = Rc::new(());
+    for n in [2, 10] {
+        let mut v = SmallVec::<Rc<()>, 4>::new();
+        for _ in 0..n {
+            v.push(Rc::clone(&rc));
+        }
+        assert_eq!(Rc::strong_count(&rc), n + 1);
+        let mut it = v.into_iter();
+        drop(it.next());
+        drop(it);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+}
+
+#[test]
+fn zst() {
+    let mut v = SmallVec::<(), 0>::new();
+    for _ in 0..100 {
+        v.push(());
+    }
+    assert_eq!((v.len(), v.capacity(), v.spilled()), (100, usize::MAX, false));
+    assert_eq!(v.into_iter().count(), 100);
+}
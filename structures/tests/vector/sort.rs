@@ -0,0 +1,234 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use structures::vector::Vec;
+
+use crate::common::{xorshift, DropCounter, Drops};
+
+fn patterns(len: usize, seed: &mut u32) -> [std::vec::Vec<u32>; 5] {
+    [
+        (0..len).map(|_| xorshift(seed)).collect(),
+        (0..len).map(|_| xorshift(seed) % 4).collect(),
+        (0..len as u32).collect(),
+        (0..len as u32).rev().collect(),
+        (0..len as u32)
+            .map(|n| if n % 16 == 0 { 0 } else { n })
+            .collect(),
+    ]
+}
+
+#[test]
+fn sort_matches_std() {
+    let mut seed = 0x9e37_79b9;
+    for len in [0, 1, 2, 7, 20, 21, 50, 100, 1000] {
+        for pattern in patterns(len, &mut seed) {
+            let mut expected = pattern.clone();
+            expected.sort();
+
+            let mut v = Vec::from(pattern.clone());
+            v.sort();
+            assert_eq!(v, expected);
+
+            let mut v = Vec::from(pattern);
+            v.sort_unstable();
+            assert_eq!(v, expected);
+        }
+    }
+}
+
+#[test]
+fn sort_is_stable() {
+    let mut seed = 7;
+    let mut v: Vec<(u32, usize)> = (0..500).map(|i| (xorshift(&mut seed) % 10, i)).collect();
+    v.sort_by_key(|&(key, _)| key);
+    assert!(v
+        .windows(2)
+        .all(|w| w[0].0 < w[1].0 || (w[0].0 == w[1].0 && w[0].1 < w[1].1)));
+}
+
+#[test]
+fn sort_by_and_zst() {
+    let mut v = Vec::from(&[3, 1, 2][..]);
+    v.sort_by(|a, b| b.cmp(a));
+    assert_eq!(v, [3, 2, 1]);
+    v.sort_unstable_by_key(|&n| n);
+    assert_eq!(v, [1, 2, 3]);
+
+    let mut v: Vec<()> = (0..100).map(|_| ()).collect();
+    v.sort();
+    v.sort_unstable();
+    assert_eq!(v.len(), 100);
+}
+
+#[test]
+fn sort_with_inconsistent_comparator() {
+    use std::cmp::Ordering;
+
+    let mut seed = 0x2545_f491;
+    for len in [2, 7, 20, 21, 50, 100, 1000] {
+        for pattern in patterns(len, &mut seed) {
+            let mut expected = pattern.clone();
+            expected.sort();
+
+            // answers at random, so the order is meaningless but the elements must survive
+            let mut random_seed = seed;
+            let mut random = |_: &u32, _: &u32| match xorshift(&mut random_seed) % 3 {
+                0 => Ordering::Less,
+                1 => Ordering::Equal,
+                _ => Ordering::Greater,
+            };
+
+            let mut v = Vec::from(pattern.clone());
+            v.sort_unstable_by(&mut random);
+            let mut v = std::vec::Vec::from(v);
+            v.sort();
+            assert_eq!(v, expected);
+
+            let mut v = Vec::from(pattern);
+            v.sort_by(&mut random);
+            let mut v = std::vec::Vec::from(v);
+            v.sort();
+            assert_eq!(v, expected);
+        }
+    }
+}
+
+#[test]
+fn sort_panic_safety() {
+    let mut seed = 1;
+    for stable in [true, false] {
+        for len in [10, 100, 1000] {
+            let drops = Drops::new();
+            let mut v: Vec<DropCounter<u32>> = (0..len)
+                .map(|_| DropCounter::with_value(xorshift(&mut seed) % 100, &drops))
+                .collect();
+
+            // the comparator panics partway through, while elements are out of place
+            let mut calls = 0;
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                let compare = |a: &DropCounter<u32>, b: &DropCounter<u32>| {
+                    calls += 1;
+                    assert!(calls < len * 2, "comparator panicked");
+                    a.value.cmp(&b.value)
+                };
+                if stable {
+                    v.sort_by(compare);
+                } else {
+                    v.sort_unstable_by(compare);
+                }
+            }));
+            assert!(result.is_err());
+
+            // every element is still there exactly once
+            assert_eq!((v.len(), drops.get()), (len, 0));
+            drop(v);
+            assert_eq!(drops.get(), len);
+        }
+    }
+}
+
+#[test]
+fn binary_search_test() {
+    let v = Vec::from(&[1, 3, 3, 5, 8][..]);
+    assert_eq!(v.binary_search(&5), Ok(3));
+    assert!(matches!(v.binary_search(&3), Ok(1 | 2)));
+    assert_eq!(v.binary_search(&0), Err(0));
+    assert_eq!(v.binary_search(&4), Err(3));
+    assert_eq!(v.binary_search(&9), Err(5));
+    assert_eq!(v.binary_search_by_key(&16, |n| n * 2), Ok(4));
+    assert_eq!(Vec::<u32>::new().binary_search_by(|n| n.cmp(&1)), Err(0));
+}
+
+#[test]
+fn dedup_test() {
+    let mut v = Vec::from(&[1, 1, 2, 3, 3, 3, 1][..]);
+    v.dedup();
+    assert_eq!(v, [1, 2, 3, 1]);
+
+    let mut v = Vec::from(&[10, 11, 20, 21, 22, 30][..]);
+    v.dedup_by_key(|n| *n / 10);
+    assert_eq!(v, [10, 20, 30]);
+
+    let mut v: Vec<String> = ["a", "A", "b", "B"].iter().map(|s| s.to_string()).collect();
+    v.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    assert_eq!(v, ["a", "b"]);
+}
+
+#[test]
+fn dedup_panic_safety() {
+    let drops = Drops::new();
+    let mut v: Vec<DropCounter<u32>> = [1, 1, 2, 2, 3]
+        .iter()
+        .map(|&value| DropCounter::with_value(value, &drops))
+        .collect();
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        v.dedup_by(|a, b| {
+            assert!(a.value != 2 || b.value != 2);
+            a.value == b.value
+        })
+    }));
+    assert!(result.is_err());
+    assert_eq!((v.len(), drops.get()), (4, 1));
+    drop(v);
+    assert_eq!(drops.get(), 5);
+}
+
+#[test]
+fn retain_test() {
+    let mut v: Vec<u32> = (0..10).collect();
+    v.retain(|n| n % 3 == 0);
+    assert_eq!(v, [0, 3, 6, 9]);
+
+    v.retain_mut(|n| {
+        *n += 1;
+        *n > 1
+    });
+    assert_eq!(v, [4, 7, 10]);
+}
+
+#[test]
+fn retain_panic_safety() {
+    let drops = Drops::new();
+    let mut v: Vec<DropCounter<u32>> = (0..6)
+        .map(|value| DropCounter::with_value(value, &drops))
+        .collect();
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        v.retain(|e| {
+            assert!(e.value < 4);
+            e.value % 2 == 0
+        })
+    }));
+    assert!(result.is_err());
+    let values: std::vec::Vec<u32> = v.iter().map(|e| e.value).collect();
+    assert_eq!((values, drops.get()), (vec![0, 2, 4, 5], 2));
+    drop(v);
+    assert_eq!(drops.get(), 6);
+}
+
+#[test]
+fn rotate_test() {
+    for len in 0..20 {
+        for mid in 0..=len {
+            let mut expected: std::vec::Vec<usize> = (0..len).collect();
+            expected.rotate_left(mid);
+            let mut v: Vec<usize> = (0..len).collect();
+            v.rotate_left(mid);
+            assert_eq!(v, expected);
+
+            expected.rotate_right(mid);
+            v.rotate_right(mid);
+            assert_eq!(v, expected);
+        }
+    }
+
+    let mut v: Vec<()> = (0..5).map(|_| ()).collect();
+    v.rotate_left(2);
+    assert_eq!(v.len(), 5);
+}
+
+#[test]
+#[should_panic]
+fn rotate_out_of_bounds() {
+    let mut v = Vec::from(&[1, 2][..]);
+    v.rotate_left(3);
+}
@@ -0,0 +1,41 @@
+use structures::vector::Vec;
+
+#[test]
+fn splice_same_len() {
+    let mut v: Vec<usize> = (0..5).collect();
+    let removed: Vec<usize> = v.splice(1..3, [10, 20]).collect();
+    assert_eq!(&removed[..], &[1, 2]);
+    assert_eq!(&v[..], &[0, 10, 20, 3, 4]);
+}
+
+#[test]
+fn splice_longer() {
+    let mut v: Vec<usize> = (0..5).collect();
+    v.splice(1..2, 10..15);
+    assert_eq!(&v[..], &[0, 10, 11, 12, 13, 14, 2, 3, 4]);
+
+    // filter hides the exact length, so the remainder has to be collected first
+    v.splice(..1, (100..110).filter(|x| x % 2 == 0));
+    assert_eq!(&v[..], &[100, 102, 104, 106, 108, 10, 11, 12, 13, 14, 2, 3, 4]);
+}
+
+#[test]
+fn splice_shorter() {
+    let mut v: Vec<usize> = (0..5).collect();
+    v.splice(1..4, [7]);
+    assert_eq!(&v[..], &[0, 7, 4]);
+}
+
+#[test]
+fn splice_at_end() {
+    let mut v: Vec<usize> = (0..3).collect();
+    v.splice(3.., [3, 4, 5]);
+    assert_eq!(&v[..], &[0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn splice_strings() {
+    let mut v: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    drop(v.splice(..2, ["x".to_string(), "y".to_string(), "z".to_string()]));
+    assert_eq!(&v[..], &["x", "y", "z", "c"]);
+}
@@ -1,5 +1,8 @@
-use structures::vector::Vec;
+use std::alloc::{AllocError, Allocator, Global, Layout};
+use std::ptr::NonNull;
+use structures::vector::{Doubling, OneAndHalf, TryReserveError, Vec};
 
+#[allow(clippy::upper_case_acronyms)]
 struct ZST;
 const UMAX: usize = usize::MAX;
 
@@ -46,3 +49,334 @@ fn cap_test() {
     }
     assert_eq!((v.capacity(), v.len()), (128, 100));
 }
+
+#[test]
+fn allocator_test() {
+    let mut v = Vec::<usize, Global>::new_in(Global);
+    for num in 0..10 {
+        v.push(num);
+    }
+    assert_eq!(v.iter().sum::<usize>(), 45);
+    let (ptr, len, cap, alloc) = v.into_raw_parts_with_alloc();
+    let v: Vec<usize> = unsafe { Vec::from_raw_parts_in(ptr, len, cap, alloc) };
+    assert_eq!((v.capacity(), v.len()), (16, 10));
+}
+
+#[test]
+fn growth_policy_test() {
+    let mut v = Vec::<usize, Global, OneAndHalf>::with_capacity(4);
+    v.push(0);
+    assert_eq!((v.capacity(), v.len()), (4, 1));
+    for num in 1..10 {
+        v.push(num);
+    }
+    assert_eq!((v.capacity(), v.len()), (13, 10));
+    assert_eq!(v.drain(2..).sum::<usize>(), 44);
+
+    // switching the policy keeps the allocation and the elements
+    let ptr = v.as_ptr();
+    let v = v.into_policy::<Doubling>();
+    assert_eq!((v.as_ptr(), v.len(), v.capacity()), (ptr, 2, 13));
+
+    let v: Vec<u8, Global, OneAndHalf> = (0..20).collect();
+    assert_eq!(v.len(), 20);
+    assert!(Vec::<u8, Global, OneAndHalf>::default().is_empty());
+}
+
+#[test]
+fn shrink_test() {
+    let mut v: Vec<usize> = (0..10).collect();
+    v.reserve(50);
+    v.shrink_to(20);
+    assert_eq!((v.capacity(), v.len()), (20, 10));
+    v.shrink_to(0);
+    assert_eq!((v.capacity(), v.len()), (10, 10));
+    v.truncate(3);
+    v.shrink_to_fit();
+    assert_eq!((v.capacity(), &v[..]), (3, &[0, 1, 2][..]));
+    v.clear();
+    v.shrink_to_fit();
+    assert_eq!(v.capacity(), 0);
+    v.push(7);
+    assert_eq!(&v[..], &[7]);
+}
+
+#[test]
+fn resize_test() {
+    let mut v: Vec<String> = Vec::new();
+    v.resize(3, "a".to_string());
+    assert_eq!(&v[..], &["a", "a", "a"]);
+    v.resize(1, "b".to_string());
+    assert_eq!(&v[..], &["a"]);
+
+    let mut next = 0;
+    v.resize_with(4, || {
+        next += 1;
+        next.to_string()
+    });
+    assert_eq!(&v[..], &["a", "1", "2", "3"]);
+}
+
+#[test]
+fn split_off_test() {
+    let mut v: Vec<usize> = (0..6).collect();
+    let tail = v.split_off(4);
+    assert_eq!((&v[..], &tail[..]), (&[0, 1, 2, 3][..], &[4, 5][..]));
+    let all = v.split_off(0);
+    assert_eq!((v.len(), all.len()), (0, 4));
+}
+
+#[test]
+fn zst_shrink_test() {
+    let mut v = Vec::<ZST>::new();
+    v.resize_with(10, || ZST);
+    v.truncate(4);
+    v.shrink_to_fit();
+    assert_eq!((v.capacity(), v.len()), (UMAX, 4));
+    let other = v.split_off(1);
+    assert_eq!((v.len(), other.len()), (1, 3));
+}
+
+#[test]
+fn std_conversion_test() {
+    let std_vec = vec![1, 2, 3];
+    let ptr = std_vec.as_ptr();
+    let v: Vec<i32> = std_vec.into();
+    assert_eq!((v.as_ptr(), &v[..]), (ptr, &[1, 2, 3][..]));
+
+    let back: std::vec::Vec<i32> = v.into();
+    assert_eq!((back.as_ptr(), back), (ptr, vec![1, 2, 3]));
+
+    let zst: Vec<ZST> = std::vec::Vec::from([ZST, ZST]).into();
+    let zst: std::vec::Vec<ZST> = zst.into();
+    assert_eq!(zst.len(), 2);
+}
+
+#[test]
+fn boxed_slice_test() {
+    let mut v: Vec<_> = Vec::with_capacity(10);
+    v.extend([1, 2, 3]);
+    let b = v.into_boxed_slice();
+    assert_eq!(&*b, &[1, 2, 3]);
+
+    let v = Vec::from(b);
+    assert_eq!((v.len(), v.capacity()), (3, 3));
+    let b: Box<[i32]> = v.into();
+    assert_eq!(b.len(), 3);
+
+    let leaked: &'static mut [i32] = Vec::from(&[4, 5][..]).leak();
+    leaked[0] = 6;
+    assert_eq!(leaked, &[6, 5]);
+}
+
+#[test]
+fn std_traits_test() {
+    use std::borrow::Borrow;
+    use std::collections::HashSet;
+
+    let v: Vec<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+    let c = v.clone();
+    assert_eq!(v, c);
+    assert_eq!(format!("{:?}", c), r#"["a", "b"]"#);
+
+    let mut set = HashSet::new();
+    set.insert(Vec::from(&[1, 2][..]));
+    assert!(set.contains(&[1, 2][..]));
+
+    let v = Vec::from(&[1, 2, 3][..]);
+    assert_eq!(v, [1, 2, 3]);
+    assert_eq!(v, &[1, 2, 3][..]);
+    assert_eq!(&[1, 2, 3][..], v);
+    assert_eq!(v, vec![1, 2, 3]);
+    let bigger = Vec::from(&[1, 3][..]);
+    assert!(v < bigger);
+    let s: &[i32] = v.borrow();
+    assert_eq!(s.len(), 3);
+
+    let mut v = v;
+    v.extend(&[4, 5]);
+    assert_eq!(v, [1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn extend_test() {
+    let mut v: Vec<_> = Vec::new();
+    v.extend(0..3);
+    v.extend((3..10).filter(|n| n % 2 == 1));
+    v.extend(Vec::from(&[10, 11][..]));
+    v.extend(&Vec::from(&[12][..]));
+    assert_eq!(v, [0, 1, 2, 3, 5, 7, 9, 10, 11, 12]);
+
+    let mut v = Vec::<u32>::new();
+    v.extend([1, 2, 3, 4, 5]);
+    assert_eq!((v.len(), v.capacity()), (5, 5));
+}
+
+#[test]
+fn extend_from_slice_test() {
+    let mut v = Vec::from(&[1, 2][..]);
+    v.extend_from_slice(&[3, 4, 5]);
+    assert_eq!(v, [1, 2, 3, 4, 5]);
+
+    let mut v: Vec<String> = Vec::new();
+    v.extend_from_slice(&["a".to_string(), "b".to_string()]);
+    assert_eq!(v, ["a", "b"]);
+}
+
+#[test]
+fn extend_from_within_test() {
+    let mut v = Vec::from(&[0, 1, 2, 3][..]);
+    v.extend_from_within(1..3);
+    v.extend_from_within(..1);
+    assert_eq!(v, [0, 1, 2, 3, 1, 2, 0]);
+
+    let mut v: Vec<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+    v.extend_from_within(..);
+    assert_eq!(v, ["a", "b", "a", "b"]);
+}
+
+#[test]
+#[should_panic]
+fn extend_from_within_out_of_bounds() {
+    let mut v = Vec::from(&[0, 1][..]);
+    v.extend_from_within(1..3);
+}
+
+#[test]
+fn extend_panic_test() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    // the elements written before the panic stay in the vector and are dropped with it
+    let counter = Rc::new(());
+    let mut v: Vec<_> = Vec::new();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        v.extend((0..4).map(|n| {
+            assert!(n < 2);
+            counter.clone()
+        }));
+    }));
+    assert!(result.is_err());
+    assert_eq!(v.len(), 2);
+    drop(v);
+    assert_eq!(Rc::strong_count(&counter), 1);
+}
+
+#[test]
+fn collect_reuses_allocation_test() {
+    let v: Vec<usize> = (0..10).collect();
+    let ptr = v.as_ptr();
+    let v: Vec<usize> = v.into_iter().collect();
+    assert_eq!(v.as_ptr(), ptr);
+
+    let mut iter = v.into_iter();
+    iter.next();
+    iter.next_back();
+    let v: Vec<usize> = iter.collect();
+    assert_eq!((v.as_ptr(), v.capacity()), (ptr, 10));
+    assert_eq!(v, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+    let mut iter = v.into_iter();
+    iter.nth(6);
+    let v: Vec<usize> = iter.collect();
+    assert_ne!(v.as_ptr(), ptr);
+    assert_eq!((v.capacity(), &v[..]), (4, &[8][..]));
+}
+
+/// Forwards to `Global`, but refuses allocations larger than `max_size` bytes.
+#[derive(Clone, Copy)]
+struct LimitAlloc {
+    max_size: usize,
+}
+
+unsafe impl Allocator for LimitAlloc {
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.size() > self.max_size {
+            return Err(AllocError);
+        }
+        Global.allocate(layout)
+    }
+
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        Global.deallocate(ptr, layout)
+    }
+}
+
+#[test]
+fn try_with_capacity_test() {
+    let alloc = LimitAlloc { max_size: 64 };
+    let v = Vec::<u64, _>::try_with_capacity_in(8, alloc).unwrap();
+    assert_eq!(v.capacity(), 8);
+
+    let layout = Layout::array::<u64>(9).unwrap();
+    let err = Vec::<u64, _>::try_with_capacity_in(9, alloc).unwrap_err();
+    assert_eq!(err, TryReserveError::AllocError { layout });
+    assert_eq!(
+        err.to_string(),
+        "memory allocation failed because the allocator failed for 72 bytes aligned to 8"
+    );
+
+    let err = Vec::<u64>::try_with_capacity(usize::MAX).unwrap_err();
+    assert_eq!(err, TryReserveError::CapacityOverflow);
+    let err: Box<dyn std::error::Error> = Box::new(err);
+    assert_eq!(
+        err.to_string(),
+        "memory allocation failed because the computed capacity exceeded the collection's maximum"
+    );
+}
+
+#[test]
+fn try_push_insert_test() {
+    let mut v = Vec::<u64, _>::new_in(LimitAlloc { max_size: 32 });
+    for n in 0..4 {
+        v.try_push(n).unwrap();
+    }
+    // growing to 8 elements needs 64 bytes
+    // the element comes back instead of being dropped
+    assert!(matches!(
+        v.try_push(4),
+        Err((4, TryReserveError::AllocError { .. }))
+    ));
+    assert!(matches!(v.try_insert(0, 5), Err((5, _))));
+    assert_eq!((&v[..], v.capacity()), (&[0, 1, 2, 3][..], 4));
+
+    v.pop();
+    v.try_insert(1, 9).unwrap();
+    assert_eq!(v, [0, 9, 1, 2]);
+}
+
+#[test]
+fn try_extend_test() {
+    let mut v = Vec::<u8, _>::new_in(LimitAlloc { max_size: 16 });
+    v.try_extend(0..10).unwrap();
+    // the first element past the capacity of 10 asks for 20 bytes
+    let mut rest = 10..20;
+    assert!(v.try_extend(rest.by_ref().filter(|_| true)).is_err());
+    assert_eq!((v.len(), v.capacity()), (10, 10));
+    // nothing is taken out of the iterator before there's room for it
+    assert_eq!(rest.next(), Some(10));
+
+    let v: Vec<_> = Vec::try_from_iter((0..5).map(|n| n * 2)).unwrap();
+    assert_eq!(v, [0, 2, 4, 6, 8]);
+    assert!(Vec::<u64>::try_from_iter(std::iter::repeat_n(0, usize::MAX)).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_test() {
+    let v: Vec<String> = ["a", "b"].iter().map(|s| s.to_string()).collect();
+    let json = serde_json::to_string(&v).unwrap();
+    assert_eq!(json, r#"["a","b"]"#);
+    let back: Vec<String> = serde_json::from_str(&json).unwrap();
+    assert_eq!(back, v);
+
+    let nested: Vec<Vec<u32>> = serde_json::from_str("[[1, 2], [], [3]]").unwrap();
+    assert_eq!(
+        (nested.len(), &nested[0][..], &nested[2][..]),
+        (3, &[1, 2][..], &[3][..])
+    );
+
+    let v: Vec<u32, Global, OneAndHalf> = serde_json::from_str("[1, 2, 3]").unwrap();
+    assert_eq!(v, [1, 2, 3]);
+    assert!(serde_json::from_str::<Vec<u32>>(r#"{"a": 1}"#).is_err());
+}